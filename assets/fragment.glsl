@@ -1,100 +1,359 @@
-#version 430 core
-
-vec3 axial_to_cube(vec2 hex) {
-    float x = hex.x;
-    float y = hex.y;
-    float z = -x - y;
-    return vec3(x, y, z);
-}
-
-vec3 cube_round(vec3 frac) {
-    float x = round(frac.x);
-    float y = round(frac.y);
-    float z = round(frac.z);
-
-    float q_diff = abs(x - frac.x);
-    float r_diff = abs(y - frac.y);
-    float s_diff = abs(z - frac.z);
-
-    if (q_diff > r_diff && q_diff > s_diff) {
-        x = -y - z;
-    } else if (r_diff > s_diff) {
-        y = -x - z;
-    } else {
-        z = -x - y;
-    }
-
-    return vec3(x, y, z);
-}
-
-vec2 cube_to_axial(vec3 cube) {
-    return vec2(cube.x, cube.y);
-}
-
-vec2 axial_round(vec2 hex) {
-    return cube_to_axial(cube_round(axial_to_cube(hex)));
-}
-
-vec2 pixel_to_pointy_hex(vec2 point, float size) {
-    float x = point.x / size;
-    float y = point.y / size;
-
-    float q = (sqrt(3.0) / 3.0) * x - (1.0 / 3.0) * y;
-    float r = (2.0 / 3.0) * y;
-
-    return axial_round(vec2(q, r));
-}
-
-vec2 axial_to_doublewidth(vec2 hex) {
-    float col = 2.0 * hex.x + hex.y;
-    float row = hex.y;
-    return vec2(col, row);
-}
-
-vec2 pixel_to_doublewidth(vec2 pixel, float size) {
-    vec2 axial_pos = pixel_to_pointy_hex(pixel, size);
-    return axial_to_doublewidth(axial_pos);
-}
-
-vec2 screen_to_world(vec2 position, float camera_zoom, vec2 camera_position, vec2 camera_offset) {
-    position.x -= camera_offset.x;
-    position.y -= camera_offset.y;
-    position.x /= camera_zoom;
-    position.y /= camera_zoom;
-    position.x -= -camera_position.x;
-    position.y -= -camera_position.y;
-
-    return position;
-}
-
-layout(std430, binding = 0) buffer Data {
-    vec4 colors[];
-};
-
-uniform float camera_zoom;
-uniform vec2 camera_position;
-uniform vec2 camera_offset;
-uniform float world_height;
-uniform float world_width;
-uniform float size;
-
-out vec4 fragColor;
-
-layout(origin_upper_left) in vec4 gl_FragCoord;
-
-void main()
-{
-    vec2 cords = gl_FragCoord.xy;
-    
-    vec2 world_pos = screen_to_world(vec2(cords.x, cords.y), camera_zoom, camera_position, camera_offset);
-    vec2 hex_pos = pixel_to_doublewidth(world_pos, size);
-
-    hex_pos.x = hex_pos.x / 2.0;
-
-    if (hex_pos.x < 0 || hex_pos.y < 0 || hex_pos.x >= world_width || hex_pos.y >= world_height) {
-        discard;
-    }
-
-    vec4 color = colors[int(hex_pos.x) + int(hex_pos.y) * int(world_width)] / 255.0;
-    fragColor = color;
-}
+#version 430 core
+
+vec3 axial_to_cube(vec2 hex) {
+    float x = hex.x;
+    float y = hex.y;
+    float z = -x - y;
+    return vec3(x, y, z);
+}
+
+vec3 cube_round(vec3 frac) {
+    float x = round(frac.x);
+    float y = round(frac.y);
+    float z = round(frac.z);
+
+    float q_diff = abs(x - frac.x);
+    float r_diff = abs(y - frac.y);
+    float s_diff = abs(z - frac.z);
+
+    if (q_diff > r_diff && q_diff > s_diff) {
+        x = -y - z;
+    } else if (r_diff > s_diff) {
+        y = -x - z;
+    } else {
+        z = -x - y;
+    }
+
+    return vec3(x, y, z);
+}
+
+vec2 cube_to_axial(vec3 cube) {
+    return vec2(cube.x, cube.y);
+}
+
+vec2 axial_round(vec2 hex) {
+    return cube_to_axial(cube_round(axial_to_cube(hex)));
+}
+
+vec2 pixel_to_pointy_hex(vec2 point, float size) {
+    float x = point.x / size;
+    float y = point.y / size;
+
+    float q = (sqrt(3.0) / 3.0) * x - (1.0 / 3.0) * y;
+    float r = (2.0 / 3.0) * y;
+
+    return axial_round(vec2(q, r));
+}
+
+vec2 axial_to_doublewidth(vec2 hex) {
+    float col = 2.0 * hex.x + hex.y;
+    float row = hex.y;
+    return vec2(col, row);
+}
+
+vec2 pixel_to_doublewidth(vec2 pixel, float size) {
+    vec2 axial_pos = pixel_to_pointy_hex(pixel, size);
+    return axial_to_doublewidth(axial_pos);
+}
+
+float hex_edge_distance(vec2 point, float size) {
+    vec2 p = abs(point);
+    float inner_radius = size * sqrt(3.0) / 2.0;
+    return inner_radius - max(p.x, 0.5 * p.x + (sqrt(3.0) / 2.0) * p.y);
+}
+
+vec2 screen_to_world(vec2 position, float camera_zoom, vec2 camera_position, vec2 camera_offset, float camera_rotation) {
+    position.x -= camera_offset.x;
+    position.y -= camera_offset.y;
+    position.x /= camera_zoom;
+    position.y /= camera_zoom;
+    float s = sin(-camera_rotation);
+    float c = cos(-camera_rotation);
+    position = vec2(position.x * c - position.y * s, position.x * s + position.y * c);
+    position.x -= -camera_position.x;
+    position.y -= -camera_position.y;
+
+    return position;
+}
+
+layout(std430, binding = 0) buffer Data {
+    vec4 colors[];
+};
+
+// Two entries per cell: (altitude, temperature, vegetation, beach_bias) and
+// (cliff_bias, lake_bias, cell type, water).
+layout(std430, binding = 1) buffer Fields {
+    vec4 fields[];
+};
+
+// Downsampled world colors packed as RGBA8, level 1 first; level 0 is `colors`.
+layout(std430, binding = 2) buffer Lod {
+    uint lod_colors[];
+};
+
+// Values of the named layer shown by `OVERLAY_LAYER`, one per cell.
+layout(std430, binding = 5) buffer LayerValues {
+    float layer_values[];
+};
+
+const int MAX_LOD_LEVELS = 16;
+
+const int OVERLAY_BIOME = 0;
+const int OVERLAY_ALTITUDE = 1;
+const int OVERLAY_TEMPERATURE = 2;
+const int OVERLAY_VEGETATION = 3;
+const int OVERLAY_BEACH_BIAS = 4;
+const int OVERLAY_CLIFF_BIAS = 5;
+const int OVERLAY_LAKE_BIAS = 6;
+const int OVERLAY_LAYER = 7;
+
+// Kind of the uploaded layer, `LAYER_NONE` when the world has no layer to show.
+const int LAYER_NONE = -1;
+const int LAYER_SCALAR = 0;
+const int LAYER_CATEGORY = 1;
+const int LAYER_OWNER = 2;
+
+uniform int layer_kind;
+uniform vec2 layer_range;
+
+vec3 gradient(vec3 low, vec3 middle, vec3 high, float value) {
+    value = clamp(value, 0.0, 1.0);
+    if (value < 0.5) {
+        return mix(low, middle, value * 2.0);
+    }
+    return mix(middle, high, value * 2.0 - 1.0);
+}
+
+vec3 overlay_color(int overlay, int cell) {
+    vec4 first = fields[cell * 2];
+    vec4 second = fields[cell * 2 + 1];
+
+    if (overlay == OVERLAY_ALTITUDE) {
+        return gradient(vec3(0.05, 0.1, 0.35), vec3(0.35, 0.6, 0.3), vec3(1.0, 1.0, 1.0), first.x);
+    } else if (overlay == OVERLAY_TEMPERATURE) {
+        return gradient(vec3(0.2, 0.3, 0.9), vec3(0.95, 0.95, 0.8), vec3(0.85, 0.15, 0.1), first.y);
+    } else if (overlay == OVERLAY_VEGETATION) {
+        return gradient(vec3(0.55, 0.4, 0.2), vec3(0.8, 0.8, 0.4), vec3(0.1, 0.5, 0.1), first.z);
+    } else if (overlay == OVERLAY_BEACH_BIAS) {
+        return gradient(vec3(0.1, 0.1, 0.2), vec3(0.5, 0.45, 0.4), vec3(1.0, 0.9, 0.5), first.w);
+    } else if (overlay == OVERLAY_CLIFF_BIAS) {
+        return gradient(vec3(0.1, 0.1, 0.1), vec3(0.5, 0.4, 0.5), vec3(1.0, 0.85, 0.8), second.x);
+    } else {
+        return gradient(vec3(0.1, 0.05, 0.0), vec3(0.3, 0.5, 0.6), vec3(0.6, 0.9, 1.0), second.y);
+    }
+}
+
+// Distinct colors for category values and owner ids.
+vec3 category_color(uint value) {
+    uint hash = value * 2654435761u;
+    hash ^= hash >> 15;
+    hash *= 2246822519u;
+    hash ^= hash >> 13;
+    return vec3(hash & 255u, (hash >> 8) & 255u, (hash >> 16) & 255u) / 255.0 * 0.75 + 0.25;
+}
+
+// Scalars on a gradient over the layer's range; cells without an owner keep a dimmed biome.
+vec3 layer_color(vec3 biome, int cell) {
+    float value = layer_values[cell];
+    if (layer_kind == LAYER_SCALAR) {
+        float range = max(layer_range.y - layer_range.x, 1e-6);
+        return gradient(vec3(0.05, 0.05, 0.2), vec3(0.6, 0.2, 0.5), vec3(1.0, 0.9, 0.3), (value - layer_range.x) / range);
+    } else if (layer_kind == LAYER_OWNER && value == 0.0) {
+        return biome * 0.35;
+    }
+    return category_color(uint(value));
+}
+
+uniform float camera_zoom;
+uniform vec2 camera_position;
+uniform vec2 camera_offset;
+uniform float camera_rotation;
+uniform float world_height;
+uniform float world_width;
+uniform float size;
+uniform ivec2 hovered_cell;
+uniform ivec2 selected_cell;
+uniform int overlay;
+uniform bool hillshade;
+uniform bool contours;
+uniform vec3 sun_direction;
+uniform float ambient;
+uniform float relief;
+uniform float sea_level;
+uniform float contour_interval;
+uniform bool grid;
+uniform int edge_mode;
+uniform int lod_levels;
+uniform int lod_offsets[MAX_LOD_LEVELS];
+uniform int lod_widths[MAX_LOD_LEVELS];
+
+// Doublewidth offsets of the six neighbors: E, SE, SW, W, NW, NE.
+const ivec2 NEIGHBORS[6] = ivec2[6](
+    ivec2(2, 0), ivec2(1, 1), ivec2(-1, 1), ivec2(-2, 0), ivec2(-1, -1), ivec2(1, -1)
+);
+
+const int EDGE_NONE = 0;
+const int EDGE_COASTLINES = 1;
+const int EDGE_BIOMES = 2;
+
+const float GRID_FADE_START = 0.25;
+const float GRID_FADE_END = 0.6;
+
+int cell_at(ivec2 doublewidth) {
+    if (doublewidth.x < 0 || doublewidth.y < 0 || doublewidth.x >= int(world_width) * 2 || doublewidth.y >= int(world_height)) {
+        return -1;
+    }
+    return doublewidth.x / 2 + doublewidth.y * int(world_width);
+}
+
+float altitude_at(ivec2 doublewidth, float fallback) {
+    int index = cell_at(doublewidth);
+    if (index < 0) {
+        return fallback;
+    }
+    return fields[index * 2].x;
+}
+
+// Index into NEIGHBORS of the hex side closest to `local`, or -1 when it is more than
+// `width` screen pixels away.
+int nearest_edge(vec2 local, float width) {
+    float inner_radius = size * sqrt(3.0) / 2.0;
+    int nearest = -1;
+    float nearest_distance = width;
+
+    for (int i = 0; i < 6; i++) {
+        vec2 direction = normalize(vec2(NEIGHBORS[i]) * vec2(sqrt(3.0) / 2.0, 3.0 / 2.0));
+        float distance = (inner_radius - dot(local, direction)) * camera_zoom;
+        if (distance < nearest_distance) {
+            nearest = i;
+            nearest_distance = distance;
+        }
+    }
+    return nearest;
+}
+
+vec4 lod_color(ivec2 cell, int level) {
+    if (level == 0) {
+        return colors[cell.x + cell.y * int(world_width)] / 255.0;
+    }
+    ivec2 lod_cell = cell >> level;
+    return unpackUnorm4x8(lod_colors[lod_offsets[level] + lod_cell.x + lod_cell.y * lod_widths[level]]);
+}
+
+// Picks the level whose cells are about one pixel wide and blends towards the next one.
+vec4 world_color(ivec2 cell) {
+    float cell_pixels = size * sqrt(3.0) * camera_zoom;
+    float lod = clamp(-log2(cell_pixels), 0.0, float(lod_levels));
+    int level = int(lod);
+    if (level >= lod_levels) {
+        return lod_color(cell, lod_levels);
+    }
+    return mix(lod_color(cell, level), lod_color(cell, level + 1), fract(lod));
+}
+
+float hillshade_factor(ivec2 doublewidth, float altitude) {
+    float neighbors[6];
+    for (int i = 0; i < 6; i++) {
+        neighbors[i] = max(altitude_at(doublewidth + NEIGHBORS[i], altitude), sea_level);
+    }
+
+    float gx = (neighbors[0] - neighbors[3]) / (2.0 * sqrt(3.0) * size);
+    float gy = (neighbors[1] + neighbors[2] - neighbors[4] - neighbors[5]) / (6.0 * size);
+    vec3 normal = normalize(vec3(-gx * relief, -gy * relief, 1.0));
+
+    float light = ambient + (1.0 - ambient) * max(dot(normal, sun_direction), 0.0);
+    float flat_light = ambient + (1.0 - ambient) * max(sun_direction.z, 0.0);
+    return clamp(light / max(flat_light, 0.01), 0.0, 1.5);
+}
+
+bool on_contour(ivec2 doublewidth, float altitude, vec2 local) {
+    int edge = nearest_edge(local, 1.0);
+    if (edge < 0) {
+        return false;
+    }
+
+    float neighbor_altitude = altitude_at(doublewidth + NEIGHBORS[edge], altitude);
+    return floor(neighbor_altitude / contour_interval) != floor(altitude / contour_interval);
+}
+
+vec4 edge_color(ivec2 doublewidth, int index, vec2 local) {
+    float width = edge_mode == EDGE_COASTLINES ? 2.0 : 1.5;
+    int edge = nearest_edge(local, width);
+    if (edge < 0) {
+        return vec4(0.0);
+    }
+
+    int neighbor = cell_at(doublewidth + NEIGHBORS[edge]);
+    if (neighbor < 0) {
+        return vec4(0.0);
+    }
+
+    vec4 own = fields[index * 2 + 1];
+    vec4 other = fields[neighbor * 2 + 1];
+    if (edge_mode == EDGE_COASTLINES && own.w != other.w) {
+        return vec4(0.05, 0.1, 0.2, 0.9);
+    }
+    if (edge_mode == EDGE_BIOMES && own.z != other.z) {
+        return vec4(0.0, 0.0, 0.0, 0.5);
+    }
+    return vec4(0.0);
+}
+
+out vec4 fragColor;
+
+layout(origin_upper_left) in vec4 gl_FragCoord;
+
+void main()
+{
+    vec2 cords = gl_FragCoord.xy;
+    
+    vec2 world_pos = screen_to_world(vec2(cords.x, cords.y), camera_zoom, camera_position, camera_offset, camera_rotation);
+    vec2 hex_pos = pixel_to_doublewidth(world_pos, size);
+
+    hex_pos.x = hex_pos.x / 2.0;
+
+    if (hex_pos.x < 0 || hex_pos.y < 0 || hex_pos.x >= world_width || hex_pos.y >= world_height) {
+        discard;
+    }
+
+    ivec2 cell = ivec2(hex_pos);
+    int index = cell.x + cell.y * int(world_width);
+    vec4 color = world_color(cell);
+    if (overlay == OVERLAY_LAYER) {
+        if (layer_kind != LAYER_NONE) {
+            color.rgb = layer_color(color.rgb, index);
+        }
+    } else if (overlay != OVERLAY_BIOME) {
+        color.rgb = overlay_color(overlay, index);
+    }
+
+    ivec2 doublewidth = ivec2(hex_pos.x * 2.0, hex_pos.y);
+    vec2 center = vec2(size * sqrt(3.0) / 2.0 * doublewidth.x, size * 3.0 / 2.0 * doublewidth.y);
+    float edge_distance = hex_edge_distance(world_pos - center, size) * camera_zoom;
+
+    float altitude = fields[index * 2].x;
+    if (hillshade) {
+        color.rgb *= hillshade_factor(doublewidth, max(altitude, sea_level));
+    }
+    if (contours && on_contour(doublewidth, altitude, world_pos - center)) {
+        color.rgb *= 0.45;
+    }
+
+    if (grid) {
+        float fade = smoothstep(GRID_FADE_START, GRID_FADE_END, camera_zoom);
+        float line = 1.0 - smoothstep(0.0, 1.0, edge_distance);
+        color.rgb = mix(color.rgb, vec3(0.0), 0.35 * fade * line);
+    }
+    if (edge_mode != EDGE_NONE) {
+        vec4 edge = edge_color(doublewidth, index, world_pos - center);
+        color.rgb = mix(color.rgb, edge.rgb, edge.a);
+    }
+
+    if (cell == selected_cell && edge_distance < 3.0) {
+        color = vec4(1.0, 0.2, 0.2, 1.0);
+    } else if (cell == hovered_cell) {
+        color.rgb = mix(color.rgb, vec3(1.0), edge_distance < 2.0 ? 0.8 : 0.25);
+    }
+
+    fragColor = color;
+}
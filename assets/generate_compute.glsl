@@ -0,0 +1,294 @@
+#version 430 core
+
+// Port of the `noise` crate functions used by `WorldNoise` and of `classify`, so the CPU
+// generator stays the reference. Noise is evaluated in double precision like on the CPU.
+
+layout(local_size_x = 16, local_size_y = 16) in;
+
+layout(std430, binding = 0) buffer Data {
+    vec4 colors[];
+};
+
+layout(std430, binding = 1) buffer Fields {
+    vec4 fields[];
+};
+
+// 256 entries per Perlin source; the octaves of a layer use consecutive tables.
+layout(std430, binding = 3) readonly buffer Permutations {
+    uint permutations[];
+};
+
+struct GeneratedCell {
+    double altitude;
+    double relative_altitude;
+    double temperature;
+    double vegetation;
+    double beach_bias;
+    double cliff_bias;
+    double lake_bias;
+    uint cell_type;
+};
+
+layout(std430, binding = 4) writeonly buffer Cells {
+    GeneratedCell cells[];
+};
+
+const int LAYER_ALTITUDE = 0;
+const int LAYER_TEMPERATURE = 1;
+const int LAYER_VEGETATION = 2;
+const int LAYER_BEACH = 3;
+const int LAYER_CLIFF = 4;
+const int LAYER_LAKE = 5;
+const int LAYER_DISTORT_X = 6;
+const int LAYER_DISTORT_Y = 7;
+const int LAYER_COUNT = 8;
+
+const int THRESHOLD_COUNT = 20;
+
+const uint GRASS = 0u;
+const uint DIRT = 1u;
+const uint TREE = 2u;
+const uint WATER = 4u;
+const uint MEDIUM_WATER = 5u;
+const uint DEEP_WATER = 6u;
+const uint SAND = 7u;
+const uint SNOW = 8u;
+const uint MOUNTAIN = 9u;
+const uint MEDIUM_MOUNTAIN = 10u;
+const uint HIGH_MOUNTAIN = 11u;
+const uint TUNDRA = 12u;
+const uint SHALLOW_WATER = 13u;
+const uint ICE = 14u;
+const uint CLIFF = 15u;
+const uint MEDIUM_CLIFF = 16u;
+
+// 2 / sqrt(2), as computed by the `noise` crate.
+const double PERLIN_SCALE = 1.414213562373095LF;
+
+uniform ivec2 world_size;
+uniform double scale;
+uniform int layer_table[LAYER_COUNT];
+uniform int layer_octaves[LAYER_COUNT];
+uniform double layer_frequency[LAYER_COUNT];
+uniform double layer_lacunarity[LAYER_COUNT];
+uniform double layer_persistence[LAYER_COUNT];
+uniform double layer_scale[LAYER_COUNT];
+uniform double turbulence_power;
+uniform double cliff_attenuation;
+uniform float cliff_exponent;
+// `BiomeThresholds` in field order.
+uniform double thresholds[THRESHOLD_COUNT];
+
+uint hash(int table, ivec2 corner) {
+    int base = table * 256;
+    uint index = permutations[base + (corner.x & 255)] ^ uint(corner.y & 255);
+    return permutations[base + int(index)];
+}
+
+double gradient(int table, ivec2 corner, dvec2 point) {
+    switch (hash(table, corner) & 3u) {
+        case 0u: return point.x + point.y;
+        case 1u: return -point.x + point.y;
+        case 2u: return point.x - point.y;
+        default: return -point.x - point.y;
+    }
+}
+
+double linear(double a, double b, double alpha) {
+    precise double result = b * alpha + a * (1.0LF - alpha);
+    return result;
+}
+
+double quintic(double x) {
+    x = clamp(x, 0.0LF, 1.0LF);
+    precise double result = x * x * x * (x * (x * 6.0LF - 15.0LF) + 10.0LF);
+    return result;
+}
+
+double perlin(int table, dvec2 point) {
+    dvec2 floored = floor(point);
+    ivec2 corner = ivec2(floored);
+    dvec2 distance = point - floored;
+
+    double g00 = gradient(table, corner, distance);
+    double g10 = gradient(table, corner + ivec2(1, 0), distance - dvec2(1.0LF, 0.0LF));
+    double g01 = gradient(table, corner + ivec2(0, 1), distance - dvec2(0.0LF, 1.0LF));
+    double g11 = gradient(table, corner + ivec2(1, 1), distance - dvec2(1.0LF, 1.0LF));
+
+    double result = linear(
+        linear(g00, g01, quintic(distance.y)),
+        linear(g10, g11, quintic(distance.y)),
+        quintic(distance.x)
+    ) * PERLIN_SCALE;
+
+    return clamp(result, -1.0LF, 1.0LF);
+}
+
+double fbm(int layer, dvec2 point) {
+    precise double result = 0.0LF;
+    double attenuation = layer_persistence[layer];
+
+    point *= layer_frequency[layer];
+
+    for (int i = 0; i < layer_octaves[layer]; i++) {
+        double signal = perlin(layer_table[layer] + i, point) * attenuation;
+        attenuation *= layer_persistence[layer];
+        result += signal;
+        point *= layer_lacunarity[layer];
+    }
+
+    return result * layer_scale[layer];
+}
+
+double ridged_multi(int layer, dvec2 point) {
+    precise double result = 0.0LF;
+    double weight = 1.0LF;
+    double attenuation = 1.0LF;
+
+    point *= layer_frequency[layer];
+
+    for (int i = 0; i < layer_octaves[layer]; i++) {
+        double signal = 1.0LF - abs(perlin(layer_table[layer] + i, point));
+        signal *= signal;
+        signal *= weight;
+        weight = clamp(signal / cliff_attenuation, 0.0LF, 1.0LF);
+        signal *= attenuation;
+        attenuation *= layer_persistence[layer];
+        result += signal;
+        point *= layer_lacunarity[layer];
+    }
+
+    return result * layer_scale[layer] - 1.0LF;
+}
+
+double turbulence(dvec2 point) {
+    double x_distort = point.x
+        + fbm(LAYER_DISTORT_X, point + dvec2(12414.0LF / 65536.0LF, 65124.0LF / 65536.0LF))
+            * turbulence_power;
+    double y_distort = point.y
+        + fbm(LAYER_DISTORT_Y, point + dvec2(26519.0LF / 65536.0LF, 18128.0LF / 65536.0LF))
+            * turbulence_power;
+
+    return fbm(LAYER_VEGETATION, dvec2(x_distort, y_distort));
+}
+
+double unit(double value) {
+    return (value + 1.0LF) / 2.0LF;
+}
+
+// Same branches as `classify`; returns the type and the altitude above its lower bound.
+uint classify(double altitude, double temp, double vegetation, double beach_bias,
+              double cliff_bias, out double relative_altitude) {
+    double land = thresholds[7];
+    double shallow_water = thresholds[16];
+
+    if (altitude > thresholds[0]) {
+        relative_altitude = altitude - thresholds[0];
+        return SNOW;
+    } else if (altitude > thresholds[1]) {
+        relative_altitude = altitude - thresholds[1];
+        return HIGH_MOUNTAIN;
+    } else if (altitude > thresholds[2]) {
+        relative_altitude = altitude - thresholds[2];
+        return MEDIUM_MOUNTAIN;
+    } else if (altitude > thresholds[3]) {
+        relative_altitude = altitude - thresholds[3];
+        return MOUNTAIN;
+    } else if (altitude > thresholds[4] && cliff_bias > thresholds[5]) {
+        relative_altitude = altitude - thresholds[4];
+        return MEDIUM_CLIFF;
+    } else if (altitude > thresholds[4] && cliff_bias > thresholds[6]) {
+        relative_altitude = altitude - thresholds[4];
+        return CLIFF;
+    } else if (altitude > land) {
+        relative_altitude = altitude - land;
+        if (temp > thresholds[8]) {
+            return SAND;
+        } else if (temp > thresholds[9]) {
+            return DIRT;
+        } else if (temp > thresholds[10]) {
+            return vegetation > thresholds[11] ? TREE : GRASS;
+        } else if (temp > thresholds[12]) {
+            return TUNDRA;
+        }
+        return SNOW;
+    } else if (altitude > thresholds[13] && beach_bias > thresholds[14] && temp > thresholds[15]) {
+        relative_altitude = altitude - shallow_water;
+        return SAND;
+    } else if (altitude > shallow_water) {
+        relative_altitude = altitude - shallow_water;
+        return temp > thresholds[17] ? SHALLOW_WATER : ICE;
+    } else if (altitude > thresholds[18]) {
+        relative_altitude = altitude - thresholds[18];
+        return WATER;
+    } else if (altitude > thresholds[19]) {
+        relative_altitude = altitude - thresholds[19];
+        return MEDIUM_WATER;
+    }
+    relative_altitude = altitude;
+    return DEEP_WATER;
+}
+
+dvec3 increase_by_height(dvec3 color, double height, double factor) {
+    return clamp(color / (1.0LF - height * factor), 0.0LF, 255.0LF);
+}
+
+dvec3 decrease_by_height(dvec3 color, double height) {
+    double root = sign(height) * double(pow(float(abs(height)), 1.0 / 3.0));
+    return clamp(color * (1.0LF - root), 0.0LF, 255.0LF);
+}
+
+// Same colors as `Cell::get_color`; rivers are traced on the CPU afterwards.
+dvec3 cell_color(uint cell_type, double height) {
+    switch (cell_type) {
+        case GRASS: return increase_by_height(dvec3(125.0, 205.0, 127.0), height, 1.4LF);
+        case DIRT: return increase_by_height(dvec3(196.0, 210.0, 130.0), height, 1.4LF);
+        case TREE: return increase_by_height(dvec3(50.0, 150.0, 50.0), height, 1.4LF);
+        case WATER: return increase_by_height(dvec3(15.0, 15.0, 160.0), height, 7.0LF);
+        case MEDIUM_WATER: return increase_by_height(dvec3(22.0, 30.0, 64.0), height, 7.0LF);
+        case DEEP_WATER: return decrease_by_height(dvec3(30.0, 50.0, 100.0), height);
+        case SAND: return increase_by_height(dvec3(230.0, 210.0, 100.0), height, 1.4LF);
+        case SNOW: return increase_by_height(dvec3(230.0, 230.0, 230.0), height, 1.4LF);
+        case MOUNTAIN: return decrease_by_height(dvec3(100.0, 100.0, 100.0), height);
+        case MEDIUM_MOUNTAIN: return decrease_by_height(dvec3(80.0, 80.0, 80.0), height);
+        case HIGH_MOUNTAIN: return decrease_by_height(dvec3(60.0, 60.0, 60.0), height);
+        case TUNDRA: return increase_by_height(dvec3(20.0, 100.0, 20.0), height, 1.4LF);
+        case SHALLOW_WATER: return increase_by_height(dvec3(40.0, 100.0, 160.0), height, 7.0LF);
+        case ICE: return increase_by_height(dvec3(150.0, 150.0, 200.0), height, 7.0LF);
+        case CLIFF: return decrease_by_height(dvec3(150.0, 150.0, 130.0), height);
+        default: return decrease_by_height(dvec3(130.0, 130.0, 110.0), height);
+    }
+}
+
+bool is_water(uint cell_type) {
+    return cell_type == WATER || cell_type == MEDIUM_WATER || cell_type == DEEP_WATER
+        || cell_type == SHALLOW_WATER || cell_type == ICE;
+}
+
+void main() {
+    ivec2 position = ivec2(gl_GlobalInvocationID.xy);
+    if (position.x >= world_size.x || position.y >= world_size.y) {
+        return;
+    }
+
+    dvec2 point = dvec2(position) * scale;
+
+    double altitude = unit(fbm(LAYER_ALTITUDE, point));
+    double temperature = unit(fbm(LAYER_TEMPERATURE, point));
+    double vegetation = unit(turbulence(point));
+    double beach_bias = unit(fbm(LAYER_BEACH, point));
+    double cliff_bias = double(pow(float(unit(ridged_multi(LAYER_CLIFF, point))), cliff_exponent));
+    double lake_bias = unit(fbm(LAYER_LAKE, point));
+
+    double relative_altitude;
+    uint cell_type = classify(altitude, temperature, vegetation, beach_bias, cliff_bias,
+                              relative_altitude);
+
+    int cell = position.x + position.y * world_size.x;
+    colors[cell] = vec4(vec3(cell_color(cell_type, relative_altitude)), 255.0);
+    fields[cell * 2] = vec4(altitude, temperature, vegetation, beach_bias);
+    fields[cell * 2 + 1] = vec4(cliff_bias, lake_bias, float(cell_type),
+                                is_water(cell_type) ? 1.0 : 0.0);
+    cells[cell] = GeneratedCell(altitude, relative_altitude, temperature, vegetation,
+                                beach_bias, cliff_bias, lake_bias, cell_type);
+}
@@ -0,0 +1,72 @@
+#version 430 core
+
+layout(std430, binding = 0) buffer Data {
+    vec4 colors[];
+};
+
+layout(std430, binding = 2) buffer Lod {
+    uint lod_colors[];
+};
+
+const int MAX_LOD_LEVELS = 16;
+
+uniform float world_height;
+uniform float world_width;
+uniform float size;
+uniform int lod_levels;
+uniform int lod_offsets[MAX_LOD_LEVELS];
+uniform int lod_widths[MAX_LOD_LEVELS];
+uniform vec4 minimap_rect;
+uniform vec4 world_bounds;
+uniform vec2 viewport[4];
+
+out vec4 fragColor;
+
+layout(origin_upper_left) in vec4 gl_FragCoord;
+
+vec4 lod_color(ivec2 cell, int level) {
+    if (level == 0) {
+        return colors[cell.x + cell.y * int(world_width)] / 255.0;
+    }
+    ivec2 lod_cell = cell >> level;
+    return unpackUnorm4x8(lod_colors[lod_offsets[level] + lod_cell.x + lod_cell.y * lod_widths[level]]);
+}
+
+float segment_distance(vec2 point, vec2 a, vec2 b) {
+    vec2 ab = b - a;
+    float t = clamp(dot(point - a, ab) / max(dot(ab, ab), 0.0001), 0.0, 1.0);
+    return length(point - (a + ab * t));
+}
+
+void main()
+{
+    vec2 position = gl_FragCoord.xy - minimap_rect.xy;
+
+    if (position.x < 0 || position.y < 0 || position.x >= minimap_rect.z || position.y >= minimap_rect.w) {
+        discard;
+    }
+
+    if (position.x < 2.0 || position.y < 2.0 || position.x >= minimap_rect.z - 2.0 || position.y >= minimap_rect.w - 2.0) {
+        fragColor = vec4(0.1, 0.1, 0.1, 1.0);
+        return;
+    }
+
+    for (int i = 0; i < 4; i++) {
+        if (segment_distance(gl_FragCoord.xy, viewport[i], viewport[(i + 1) % 4]) < 1.0) {
+            fragColor = vec4(1.0, 1.0, 1.0, 1.0);
+            return;
+        }
+    }
+
+    vec2 world_pos = world_bounds.xy + position / minimap_rect.zw * (world_bounds.zw - world_bounds.xy);
+
+    // Nearest row and column is exact enough at minimap scale.
+    int row = int(clamp(round(world_pos.y / (size * 1.5)), 0.0, world_height - 1.0));
+    float column = (world_pos.x / (size * sqrt(3.0) / 2.0) - float(row % 2)) / 2.0;
+    ivec2 cell = ivec2(int(clamp(round(column), 0.0, world_width - 1.0)), row);
+
+    float cells_per_pixel = world_width / minimap_rect.z;
+    int level = int(clamp(ceil(log2(cells_per_pixel)), 0.0, float(lod_levels)));
+
+    fragColor = vec4(lod_color(cell, level).rgb, 1.0);
+}
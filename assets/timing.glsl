@@ -0,0 +1,44 @@
+#version 430 core
+
+const int FRAME_SAMPLES = 120;
+
+uniform float frame_times[FRAME_SAMPLES];
+uniform int frame_count;
+uniform vec4 graph_rect;
+uniform float graph_scale;
+
+out vec4 fragColor;
+
+layout(origin_upper_left) in vec4 gl_FragCoord;
+
+void main()
+{
+    vec2 position = gl_FragCoord.xy - graph_rect.xy;
+
+    if (position.x < 0 || position.y < 0 || position.x >= graph_rect.z || position.y >= graph_rect.w) {
+        discard;
+    }
+
+    int sample_index = int(position.x / graph_rect.z * float(FRAME_SAMPLES));
+    float milliseconds = (1.0 - position.y / graph_rect.w) * graph_scale;
+    float pixel = graph_scale / graph_rect.w;
+
+    vec4 color = vec4(0.0, 0.0, 0.0, 0.6);
+
+    float frame_time = frame_times[sample_index];
+    if (sample_index >= FRAME_SAMPLES - frame_count && milliseconds <= frame_time) {
+        if (frame_time < 1000.0 / 60.0) {
+            color = vec4(0.3, 0.9, 0.3, 0.9);
+        } else if (frame_time < 1000.0 / 30.0) {
+            color = vec4(0.95, 0.8, 0.2, 0.9);
+        } else {
+            color = vec4(0.95, 0.3, 0.2, 0.9);
+        }
+    }
+
+    if (abs(milliseconds - 1000.0 / 60.0) < pixel || abs(milliseconds - 1000.0 / 30.0) < pixel) {
+        color = vec4(1.0, 1.0, 1.0, 0.8);
+    }
+
+    fragColor = color;
+}
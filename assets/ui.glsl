@@ -0,0 +1,13 @@
+#version 430 core
+
+layout (binding = 0) uniform sampler2D font;
+
+layout (location = 0) in vec2 uv;
+layout (location = 1) in vec4 color;
+
+out vec4 fragColor;
+
+void main()
+{
+    fragColor = vec4(color.rgb, color.a * texture(font, uv).r);
+}
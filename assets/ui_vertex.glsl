@@ -0,0 +1,17 @@
+#version 430 core
+
+layout (location = 0) in vec2 aPos;
+layout (location = 1) in vec2 aUv;
+layout (location = 2) in vec4 aColor;
+
+uniform vec2 screen_size;
+
+layout (location = 0) out vec2 uv;
+layout (location = 1) out vec4 color;
+
+void main()
+{
+    uv = aUv;
+    color = aColor;
+    gl_Position = vec4(aPos / screen_size * vec2(2.0, -2.0) + vec2(-1.0, 1.0), 0.0, 1.0);
+}
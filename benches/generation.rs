@@ -0,0 +1,85 @@
+use criterion::{BatchSize, Criterion, Throughput, criterion_group, criterion_main};
+use rayon::prelude::*;
+
+use civilizations::{
+    WorldGenConfig, generate_river, generate_world_sequential, generate_world_with_config,
+    world::{WorldNoise, classify},
+};
+
+const SEED: u64 = 1;
+const WIDTH: i32 = 250;
+const HEIGHT: i32 = 250;
+
+type Sample = fn(&WorldNoise, i64, i64) -> f64;
+
+fn sample_field(noise: &WorldNoise, sample: Sample) -> Vec<f64> {
+    (0..HEIGHT as i64)
+        .into_par_iter()
+        .flat_map_iter(|y| (0..WIDTH as i64).map(move |x| sample(noise, x, y)))
+        .collect()
+}
+
+fn generation(c: &mut Criterion) {
+    let config = WorldGenConfig::new();
+    let noise = WorldNoise::new(SEED, &config);
+
+    let mut group = c.benchmark_group("generation");
+    group.sample_size(10);
+    group.throughput(Throughput::Elements((WIDTH * HEIGHT) as u64));
+
+    let stages: [(&str, Sample); 6] = [
+        ("altitude", WorldNoise::altitude),
+        ("temperature", WorldNoise::temperature),
+        ("vegetation", WorldNoise::vegetation),
+        ("beach_bias", WorldNoise::beach_bias),
+        ("cliff_bias", WorldNoise::cliff_bias),
+        ("lake_bias", WorldNoise::lake_bias),
+    ];
+    for (name, sample) in stages {
+        group.bench_function(name, |b| b.iter(|| sample_field(&noise, sample)));
+    }
+
+    let world = generate_world_with_config(SEED, WIDTH, HEIGHT, &config);
+    group.bench_function("classify", |b| {
+        b.iter(|| {
+            world
+                .map
+                .par_iter()
+                .flatten()
+                .map(|cell| {
+                    classify(
+                        cell.altitude,
+                        cell.temperature,
+                        cell.vegetation,
+                        cell.beach_bias,
+                        cell.cliff_bias,
+                        &config.thresholds,
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    });
+
+    let map: Vec<_> = (0..HEIGHT as usize)
+        .map(|y| noise.row(y, WIDTH, &config.thresholds))
+        .collect();
+    group.bench_function("rivers", |b| {
+        b.iter_batched(
+            || map.clone(),
+            |mut map| generate_river(&mut map, SEED, &config),
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("world_sequential", |b| {
+        b.iter(|| generate_world_sequential(SEED, WIDTH, HEIGHT, &config))
+    });
+    group.bench_function("world_parallel", |b| {
+        b.iter(|| generate_world_with_config(SEED, WIDTH, HEIGHT, &config))
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, generation);
+criterion_main!(benches);
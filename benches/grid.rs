@@ -0,0 +1,129 @@
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+
+use civilizations::{
+    Cell, CellType, GridField, WorldGenConfig, WorldGrid, generate_world_with_config, get_neighbors,
+};
+
+const SEED: u64 = 1;
+const WIDTH: i32 = 500;
+const HEIGHT: i32 = 500;
+
+fn map_memory(map: &[Vec<Cell>]) -> usize {
+    std::mem::size_of::<Vec<Vec<Cell>>>()
+        + std::mem::size_of_val(map)
+        + map
+            .iter()
+            .map(|row| std::mem::size_of_val(row.as_slice()))
+            .sum::<usize>()
+}
+
+// Lowest neighbour of every cell, the step rivers take.
+fn map_lowest_neighbors(map: &[Vec<Cell>]) -> usize {
+    let mut count = 0;
+    for y in 0..map.len() {
+        for x in 0..map[y].len() {
+            let lowest = get_neighbors(map, x * 2 + y % 2, y)
+                .into_iter()
+                .min_by(|a, b| {
+                    map[a.1][a.0 / 2]
+                        .altitude
+                        .total_cmp(&map[b.1][b.0 / 2].altitude)
+                });
+            if let Some(lowest) = lowest {
+                count += lowest.0 % 2;
+            }
+        }
+    }
+    count
+}
+
+fn grid_lowest_neighbors(grid: &WorldGrid) -> usize {
+    let mut count = 0;
+    for hex in grid.hexes() {
+        let lowest = grid.neighbors(hex).min_by(|a, b| {
+            grid.altitude_at(*a)
+                .unwrap()
+                .total_cmp(&grid.altitude_at(*b).unwrap())
+        });
+        if let Some(lowest) = lowest {
+            count += lowest.0 as usize % 2;
+        }
+    }
+    count
+}
+
+fn grid(c: &mut Criterion) {
+    let world = generate_world_with_config(SEED, WIDTH, HEIGHT, &WorldGenConfig::new());
+    let grid = WorldGrid::from_map(&world.map);
+
+    println!(
+        "memory: Vec<Vec<Cell>> {} bytes, WorldGrid {} bytes",
+        map_memory(&world.map),
+        grid.memory()
+    );
+
+    let mut group = c.benchmark_group("grid");
+    group.throughput(Throughput::Elements((WIDTH * HEIGHT) as u64));
+
+    group.bench_function("altitude_sum/map", |b| {
+        b.iter(|| {
+            world
+                .map
+                .iter()
+                .flatten()
+                .map(|cell| cell.altitude)
+                .sum::<f64>()
+        })
+    });
+    group.bench_function("altitude_sum/grid", |b| {
+        b.iter(|| {
+            grid.field(GridField::Altitude)
+                .iter()
+                .map(|altitude| *altitude as f64)
+                .sum::<f64>()
+        })
+    });
+
+    group.bench_function("count_water/map", |b| {
+        b.iter(|| {
+            world
+                .map
+                .iter()
+                .flatten()
+                .filter(|cell| cell.cell_type.is_water())
+                .count()
+        })
+    });
+    group.bench_function("count_water/grid", |b| {
+        b.iter(|| grid.cell_types().iter().filter(|t| t.is_water()).count())
+    });
+
+    group.bench_function("lowest_neighbor/map", |b| {
+        b.iter(|| map_lowest_neighbors(&world.map))
+    });
+    group.bench_function("lowest_neighbor/grid", |b| {
+        b.iter(|| grid_lowest_neighbors(&grid))
+    });
+
+    group.bench_function("set_cell_type/map", |b| {
+        let mut map = world.map.clone();
+        b.iter(|| {
+            for row in map.iter_mut() {
+                for cell in row.iter_mut() {
+                    cell.cell_type = CellType::Grass;
+                }
+            }
+        })
+    });
+    group.bench_function("set_cell_type/grid", |b| {
+        let mut grid = grid.clone();
+        b.iter(|| grid.cell_types_mut().fill(CellType::Grass))
+    });
+
+    group.bench_function("from_map", |b| b.iter(|| WorldGrid::from_map(&world.map)));
+
+    group.finish();
+}
+
+criterion_group!(benches, grid);
+criterion_main!(benches);
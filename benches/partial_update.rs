@@ -0,0 +1,55 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+use civilizations::{
+    DirtyRegions, generate_world, generate_world_colors, generate_world_fields, get_world_size,
+    lod, update_world_colors, update_world_fields,
+};
+
+fn partial_update(c: &mut Criterion) {
+    let world = generate_world(1, 1000, 1000);
+    let (world_width, world_height) = get_world_size(&world.map);
+
+    let mut colors = generate_world_colors(&world.map);
+    let mut fields = generate_world_fields(&world.map);
+    let mut levels = lod::generate_color_pyramid(&colors, world_width, world_height);
+
+    let mut group = c.benchmark_group("partial_update");
+    group.sample_size(20);
+
+    for churn in [0.01, 0.1, 1.0] {
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut dirty = DirtyRegions::new(world_width, world_height);
+        for y in 0..world_height as usize {
+            for x in 0..world_width as usize {
+                if rng.random_bool(churn) {
+                    dirty.mark(x, y);
+                }
+            }
+        }
+
+        group.bench_with_input(
+            BenchmarkId::from_parameter(format!("{}%", churn * 100.0)),
+            &dirty,
+            |b, dirty| {
+                b.iter(|| {
+                    update_world_colors(&world.map, &mut colors, dirty);
+                    update_world_fields(&world.map, &mut fields, dirty);
+                    lod::update_color_pyramid(
+                        &mut levels,
+                        &colors,
+                        world_width,
+                        world_height,
+                        dirty,
+                    );
+                    dirty.ranges()
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, partial_update);
+criterion_main!(benches);
@@ -0,0 +1,88 @@
+use std::f64::consts::TAU;
+
+use crate::hex::{self, HEX_SIZE};
+
+fn rotate(point: (f64, f64), angle: f64) -> (f64, f64) {
+    let (sin, cos) = angle.sin_cos();
+    (point.0 * cos - point.1 * sin, point.0 * sin + point.1 * cos)
+}
+
+pub struct Camera2D {
+    pub offset: (f64, f64),
+    pub position: (f64, f64),
+    pub rotation: f64,
+    pub zoom: f64,
+}
+
+impl Camera2D {
+    pub fn get_screen_to_world(self: &Self, screen_x: f64, screen_y: f64) -> (f64, f64) {
+        let mut world_x = screen_x;
+        let mut world_y = screen_y;
+        world_x -= self.offset.0;
+        world_y -= self.offset.1;
+        world_x /= self.zoom;
+        world_y /= self.zoom;
+        (world_x, world_y) = rotate((world_x, world_y), -self.rotation);
+        world_x -= -self.position.0;
+        world_y -= -self.position.1;
+
+        (world_x, world_y)
+    }
+
+    pub fn get_world_to_screen(self: &Self, world_x: f64, world_y: f64) -> (f64, f64) {
+        let mut screen_x = world_x;
+        let mut screen_y = world_y;
+        screen_x -= self.position.0;
+        screen_y -= self.position.1;
+        (screen_x, screen_y) = rotate((screen_x, screen_y), self.rotation);
+        screen_x *= self.zoom;
+        screen_y *= self.zoom;
+        screen_x += self.offset.0;
+        screen_y += self.offset.1;
+
+        (screen_x, screen_y)
+    }
+
+    pub fn pan(self: &mut Self, screen_dx: f64, screen_dy: f64) {
+        let (dx, dy) = rotate(
+            (screen_dx / self.zoom, screen_dy / self.zoom),
+            -self.rotation,
+        );
+        self.position = (self.position.0 - dx, self.position.1 - dy);
+    }
+
+    fn keep_world_pos(self: &mut Self, screen_x: f64, screen_y: f64, before: (f64, f64)) {
+        let after = self.get_screen_to_world(screen_x, screen_y);
+        self.position = (
+            self.position.0 + before.0 - after.0,
+            self.position.1 + before.1 - after.1,
+        );
+    }
+
+    pub fn zoom_around(self: &mut Self, screen_x: f64, screen_y: f64, zoom: f64) {
+        let before = self.get_screen_to_world(screen_x, screen_y);
+        self.zoom = zoom;
+        self.keep_world_pos(screen_x, screen_y, before);
+    }
+
+    pub fn rotate_around(self: &mut Self, screen_x: f64, screen_y: f64, angle: f64) {
+        let before = self.get_screen_to_world(screen_x, screen_y);
+        self.rotation = (self.rotation + angle).rem_euclid(TAU);
+        self.keep_world_pos(screen_x, screen_y, before);
+    }
+
+    pub fn fit_to_world(world_size: (i32, i32), screen_size: (u32, u32)) -> Camera2D {
+        let (min_x, min_y, max_x, max_y) =
+            hex::hex_world_bounds(world_size.0, world_size.1, HEX_SIZE);
+
+        let zoom = (screen_size.0 as f64 / (max_x - min_x) as f64)
+            .min(screen_size.1 as f64 / (max_y - min_y) as f64);
+
+        Camera2D {
+            offset: (screen_size.0 as f64 / 2.0, screen_size.1 as f64 / 2.0),
+            position: ((min_x + max_x) as f64 / 2.0, (min_y + max_y) as f64 / 2.0),
+            rotation: 0.0,
+            zoom,
+        }
+    }
+}
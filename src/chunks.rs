@@ -0,0 +1,277 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        Arc,
+        mpsc::{self, Receiver, Sender},
+    },
+};
+
+use crate::{Cell, CellType, DirtyRegions, WorldGenConfig, generate_river, world::WorldNoise};
+
+// Chunks are square in cells; the size is even so rows keep their hex offset parity.
+pub const CHUNK_SIZE: usize = 64;
+
+pub type ChunkCoord = (i32, i32);
+
+pub struct Chunk {
+    pub map: Vec<Vec<Cell>>,
+    pub rivers: Vec<Vec<(usize, usize)>>,
+}
+
+pub fn chunk_bytes() -> usize {
+    CHUNK_SIZE * CHUNK_SIZE * std::mem::size_of::<Cell>()
+}
+
+// Stands in for cells whose chunk is still being generated.
+pub fn placeholder_cell() -> Cell {
+    Cell {
+        cell_type: CellType::DeepWater,
+        altitude: 0.0,
+        relative_altitude: 0.0,
+        temperature: 0.0,
+        vegetation: 0.0,
+        beach_bias: 0.0,
+        cliff_bias: 0.0,
+        lake_bias: 0.0,
+    }
+}
+
+// Rivers are traced inside their own chunk with a seed of its own, so a chunk never depends
+// on which chunks were generated before it.
+pub fn generate_chunk(
+    noise: &WorldNoise,
+    seed: u64,
+    config: &WorldGenConfig,
+    coord: ChunkCoord,
+) -> Chunk {
+    let origin = (
+        coord.0 as i64 * CHUNK_SIZE as i64,
+        coord.1 as i64 * CHUNK_SIZE as i64,
+    );
+
+    let mut map: Vec<Vec<Cell>> = (0..CHUNK_SIZE as i64)
+        .map(|y| {
+            (0..CHUNK_SIZE as i64)
+                .map(|x| noise.cell(origin.0 + x, origin.1 + y, &config.thresholds))
+                .collect()
+        })
+        .collect();
+
+    let chunk_seed = seed
+        ^ (coord.0 as u32 as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)
+        ^ (coord.1 as u32 as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+    let rivers = generate_river(&mut map, chunk_seed, config);
+
+    Chunk { map, rivers }
+}
+
+// Generated chunks by coordinate; chunks are generated on the rayon pool when requested and
+// picked up by `poll`.
+pub struct ChunkStore {
+    seed: u64,
+    config: WorldGenConfig,
+    noise: Arc<WorldNoise>,
+    chunks: HashMap<ChunkCoord, Chunk>,
+    pending: HashSet<ChunkCoord>,
+    // Bumped by `reset` so chunks of the previous world still in flight are dropped.
+    generation: u64,
+    sender: Sender<(u64, ChunkCoord, Chunk)>,
+    receiver: Receiver<(u64, ChunkCoord, Chunk)>,
+    // Bytes of cells kept in memory before far chunks are evicted.
+    pub budget: usize,
+}
+
+impl ChunkStore {
+    pub fn new(seed: u64, config: &WorldGenConfig, budget: usize) -> ChunkStore {
+        let (sender, receiver) = mpsc::channel();
+
+        ChunkStore {
+            seed,
+            config: *config,
+            noise: Arc::new(WorldNoise::new(seed, config)),
+            chunks: HashMap::new(),
+            pending: HashSet::new(),
+            generation: 0,
+            sender,
+            receiver,
+            budget,
+        }
+    }
+
+    pub fn reset(self: &mut Self, seed: u64, config: &WorldGenConfig) {
+        self.seed = seed;
+        self.config = *config;
+        self.noise = Arc::new(WorldNoise::new(seed, config));
+        self.chunks.clear();
+        self.pending.clear();
+        self.generation += 1;
+    }
+
+    pub fn config(self: &Self) -> &WorldGenConfig {
+        &self.config
+    }
+
+    pub fn get(self: &Self, coord: ChunkCoord) -> Option<&Chunk> {
+        self.chunks.get(&coord)
+    }
+
+    pub fn loaded(self: &Self) -> usize {
+        self.chunks.len()
+    }
+
+    pub fn pending(self: &Self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn memory(self: &Self) -> usize {
+        self.chunks.len() * chunk_bytes()
+    }
+
+    // Starts generating a chunk unless it is loaded or already on its way.
+    pub fn request(self: &mut Self, coord: ChunkCoord) {
+        if self.chunks.contains_key(&coord) || !self.pending.insert(coord) {
+            return;
+        }
+
+        let noise = Arc::clone(&self.noise);
+        let (seed, config, generation) = (self.seed, self.config, self.generation);
+        let sender = self.sender.clone();
+        rayon::spawn(move || {
+            let chunk = generate_chunk(&noise, seed, &config, coord);
+            let _ = sender.send((generation, coord, chunk));
+        });
+    }
+
+    // Stores the chunks finished since the last call and returns their coordinates.
+    pub fn poll(self: &mut Self) -> Vec<ChunkCoord> {
+        let mut finished = vec![];
+
+        for (generation, coord, chunk) in self.receiver.try_iter() {
+            if generation != self.generation {
+                continue;
+            }
+            self.pending.remove(&coord);
+            self.chunks.insert(coord, chunk);
+            finished.push(coord);
+        }
+
+        finished
+    }
+
+    // Drops the chunks farthest from `center` until the cells fit in the budget.
+    pub fn evict(self: &mut Self, center: ChunkCoord) {
+        let excess = self.memory().saturating_sub(self.budget);
+        if excess == 0 {
+            return;
+        }
+
+        let mut coords: Vec<ChunkCoord> = self.chunks.keys().copied().collect();
+        coords.sort_by_key(|coord| {
+            std::cmp::Reverse((coord.0 - center.0).abs().max((coord.1 - center.1).abs()))
+        });
+        for coord in coords.iter().take(excess.div_ceil(chunk_bytes())) {
+            self.chunks.remove(coord);
+        }
+    }
+}
+
+// A square of chunks around the camera laid out as one regular map, so the viewer can draw
+// it like a fixed size world. Map positions are relative to the `origin` chunk.
+pub struct ChunkWindow {
+    pub origin: ChunkCoord,
+    pub chunks: i32,
+}
+
+impl ChunkWindow {
+    pub fn new(chunks: i32, center: ChunkCoord) -> ChunkWindow {
+        ChunkWindow {
+            origin: (center.0 - chunks / 2, center.1 - chunks / 2),
+            chunks,
+        }
+    }
+
+    pub fn world_size(self: &Self) -> (i32, i32) {
+        let size = self.chunks * CHUNK_SIZE as i32;
+        (size, size)
+    }
+
+    pub fn center(self: &Self) -> ChunkCoord {
+        (
+            self.origin.0 + self.chunks / 2,
+            self.origin.1 + self.chunks / 2,
+        )
+    }
+
+    pub fn contains(self: &Self, coord: ChunkCoord) -> bool {
+        coord.0 >= self.origin.0
+            && coord.1 >= self.origin.1
+            && coord.0 < self.origin.0 + self.chunks
+            && coord.1 < self.origin.1 + self.chunks
+    }
+
+    // Chunks of the window, nearest to the center first.
+    pub fn coords(self: &Self) -> Vec<ChunkCoord> {
+        let center = self.center();
+        let mut coords: Vec<ChunkCoord> = (0..self.chunks)
+            .flat_map(|y| (0..self.chunks).map(move |x| (x, y)))
+            .map(|(x, y)| (self.origin.0 + x, self.origin.1 + y))
+            .collect();
+        coords.sort_by_key(|coord| (coord.0 - center.0).abs().max((coord.1 - center.1).abs()));
+        coords
+    }
+
+    // The chunk holding a map position, which may lie outside the window.
+    pub fn chunk_of(self: &Self, x: i32, y: i32) -> ChunkCoord {
+        (
+            self.origin.0 + x.div_euclid(CHUNK_SIZE as i32),
+            self.origin.1 + y.div_euclid(CHUNK_SIZE as i32),
+        )
+    }
+
+    pub fn placeholder_map(self: &Self) -> Vec<Vec<Cell>> {
+        let (width, height) = self.world_size();
+        vec![vec![placeholder_cell(); width as usize]; height as usize]
+    }
+
+    pub fn copy_chunk(
+        self: &Self,
+        map: &mut [Vec<Cell>],
+        coord: ChunkCoord,
+        chunk: &Chunk,
+        dirty: &mut DirtyRegions,
+    ) {
+        let x0 = (coord.0 - self.origin.0) as usize * CHUNK_SIZE;
+        let y0 = (coord.1 - self.origin.1) as usize * CHUNK_SIZE;
+
+        for (y, row) in chunk.map.iter().enumerate() {
+            map[y0 + y][x0..x0 + CHUNK_SIZE].copy_from_slice(row);
+            for x in 0..CHUNK_SIZE {
+                dirty.mark(x0 + x, y0 + y);
+            }
+        }
+    }
+
+    // Rebuilds the map from the loaded chunks, with placeholders for the missing ones.
+    pub fn fill(
+        self: &Self,
+        map: &mut Vec<Vec<Cell>>,
+        store: &ChunkStore,
+        dirty: &mut DirtyRegions,
+    ) {
+        *map = self.placeholder_map();
+        dirty.mark_all();
+
+        for coord in self.coords() {
+            if let Some(chunk) = store.get(coord) {
+                self.copy_chunk(map, coord, chunk, dirty);
+            }
+        }
+    }
+
+    // Centers the window on `center` and returns how many chunks it moved.
+    pub fn move_to(self: &mut Self, center: ChunkCoord) -> (i32, i32) {
+        let old = self.origin;
+        self.origin = (center.0 - self.chunks / 2, center.1 - self.chunks / 2);
+        (self.origin.0 - old.0, self.origin.1 - old.1)
+    }
+}
@@ -0,0 +1,368 @@
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+
+use civilizations::{
+    Camera2D, Cell, CellType, Climate, DirtyRegions, HEX_SIZE, WORLD_HEIGTH, WORLD_WIDTH, World,
+    WorldGenConfig, export, generate_seed, geojson, get_world_size, reclassify, save,
+    seasons::{self, SeasonCycle},
+};
+
+use crate::{WINDOW_HEIGHT, WINDOW_WIDTH, generator::generate_timed, gpu_check, view};
+
+// Hex exports of small worlds can use big hexes; the image size itself is capped in export.
+const MAX_HEX_SIZE: f32 = 64.0;
+
+#[derive(Parser)]
+#[command(name = "civilizations", about = "Hex world generator and viewer")]
+pub struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Open the interactive viewer
+    View {
+        #[command(flatten)]
+        source: WorldSource,
+        /// Start centered on the cell `x,y`
+        #[arg(long, value_parser = parse_cell)]
+        goto: Option<(i32, i32)>,
+        /// Explore an unbounded world generated in chunks around the camera
+        #[arg(long, conflicts_with_all = ["world", "goto"])]
+        infinite: bool,
+        /// Load shaders from a directory and reload them when they change, defaults to the source `assets`
+        #[arg(long, value_name = "DIR", num_args = 0..=1, default_missing_value = concat!(env!("CARGO_MANIFEST_DIR"), "/assets"))]
+        shader_dir: Option<PathBuf>,
+    },
+    /// Generate a world and save it to a file
+    Generate {
+        #[arg(long)]
+        seed: Option<u64>,
+        #[arg(long, default_value_t = WORLD_WIDTH)]
+        width: i32,
+        #[arg(long, default_value_t = WORLD_HEIGTH)]
+        height: i32,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Render a world to a PNG image without opening a window
+    Render {
+        #[command(flatten)]
+        source: WorldSource,
+        /// Camera as `x,y,zoom[,rotation]` with rotation in degrees; fits the whole world when omitted
+        #[arg(long, value_parser = parse_camera)]
+        camera: Option<(f64, f64, f64, f64)>,
+        #[arg(long, default_value_t = WINDOW_WIDTH)]
+        width: u32,
+        #[arg(long, default_value_t = WINDOW_HEIGHT)]
+        height: u32,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Export biome, altitude, temperature and vegetation PNGs
+    Export {
+        #[command(flatten)]
+        source: WorldSource,
+        /// Render biomes as hexes of the given size instead of one pixel per cell
+        #[arg(long, value_parser = parse_hex_size)]
+        hex: Option<f32>,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Export regions and rivers as GeoJSON features
+    Geojson {
+        #[command(flatten)]
+        source: WorldSource,
+        #[arg(long)]
+        out: PathBuf,
+    },
+    /// Print cell type and altitude statistics of a saved world
+    Stats { world: PathBuf },
+    /// Run the season cycle for a number of days and report how the world changes
+    Simulate {
+        #[command(flatten)]
+        source: WorldSource,
+        /// Days to simulate, one reclassification each
+        #[arg(long)]
+        ticks: u32,
+        /// Save the world as it is after the last day
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// Generate a world with the compute shader and compare it to the CPU generator
+    GpuCheck {
+        #[arg(long, default_value_t = 42)]
+        seed: u64,
+        #[arg(long, default_value_t = WORLD_WIDTH)]
+        width: i32,
+        #[arg(long, default_value_t = WORLD_HEIGTH)]
+        height: i32,
+    },
+}
+
+#[derive(Args)]
+struct WorldSource {
+    /// World file written by `generate`; a new world is generated when omitted
+    world: Option<PathBuf>,
+    #[arg(long, conflicts_with = "world")]
+    seed: Option<u64>,
+    /// Raise or lower the sea by this much altitude
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    sea_level: f64,
+    /// Warm or cool every cell by this much temperature
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    temperature_offset: f64,
+}
+
+impl WorldSource {
+    fn seed(self: &Self) -> u64 {
+        self.seed.unwrap_or_else(generate_seed)
+    }
+
+    fn climate(self: &Self) -> Climate {
+        Climate {
+            sea_level: self.sea_level,
+            temperature_offset: self.temperature_offset,
+            ..Climate::new()
+        }
+    }
+
+    fn load(self: &Self) -> Result<World, String> {
+        self.load_seeded(self.seed())
+    }
+
+    // The seed is only used when no world file is given.
+    fn load_seeded(self: &Self, seed: u64) -> Result<World, String> {
+        let mut world = match &self.world {
+            Some(path) => save::load_world(path)?,
+            None => generate_timed(seed, (WORLD_WIDTH, WORLD_HEIGTH), &WorldGenConfig::new()),
+        };
+
+        let climate = self.climate();
+        if climate != Climate::new() {
+            let (world_width, world_height) = get_world_size(&world.map);
+            let thresholds = world.config.thresholds;
+            reclassify(
+                &mut world,
+                &thresholds,
+                &climate,
+                &mut DirtyRegions::new(world_width, world_height),
+            );
+        }
+        Ok(world)
+    }
+}
+
+fn parse_camera(value: &str) -> Result<(f64, f64, f64, f64), String> {
+    let parts: Vec<f64> = value
+        .split(',')
+        .map(|part| part.trim().parse::<f64>())
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.to_string())?;
+
+    match parts[..] {
+        [x, y, zoom] if zoom > 0.0 => Ok((x, y, zoom, 0.0)),
+        [x, y, zoom, rotation] if zoom > 0.0 => Ok((x, y, zoom, rotation.to_radians())),
+        _ => Err(String::from(
+            "expected `x,y,zoom[,rotation]` with a positive zoom",
+        )),
+    }
+}
+
+fn parse_hex_size(value: &str) -> Result<f32, String> {
+    let size = value.trim().parse::<f32>().map_err(|e| e.to_string())?;
+    if size.is_finite() && size > 0.0 && size <= MAX_HEX_SIZE {
+        Ok(size)
+    } else {
+        Err(format!(
+            "expected a hex size above 0 and at most {}",
+            MAX_HEX_SIZE
+        ))
+    }
+}
+
+fn parse_cell(value: &str) -> Result<(i32, i32), String> {
+    match value.split_once(',') {
+        Some((x, y)) => Ok((
+            x.trim().parse::<i32>().map_err(|e| e.to_string())?,
+            y.trim().parse::<i32>().map_err(|e| e.to_string())?,
+        )),
+        None => Err(String::from("expected `x,y`")),
+    }
+}
+
+fn print_stats(map: &Vec<Vec<Cell>>) {
+    let (world_width, world_height) = get_world_size(map);
+    let total = (world_width * world_height) as f64;
+
+    let mut counts = [0usize; CellType::ALL.len()];
+    let mut min_altitude = f64::MAX;
+    let mut max_altitude = f64::MIN;
+    let mut sum_altitude = 0.0;
+
+    for row in map {
+        for cell in row {
+            counts[cell.cell_type as usize] += 1;
+            min_altitude = min_altitude.min(cell.altitude);
+            max_altitude = max_altitude.max(cell.altitude);
+            sum_altitude += cell.altitude;
+        }
+    }
+
+    println!("Size: {}x{} ({} cells)", world_width, world_height, total);
+    println!(
+        "Altitude: min {:.4}, mean {:.4}, max {:.4}",
+        min_altitude,
+        sum_altitude / total,
+        max_altitude
+    );
+    for cell_type in CellType::ALL {
+        let count = counts[cell_type as usize];
+        println!(
+            "{:<16}{:>10}{:>9.2}%",
+            format!("{:?}", cell_type),
+            count,
+            count as f64 / total * 100.0
+        );
+    }
+}
+
+fn simulate(world: &mut World, climate: &Climate, ticks: u32) {
+    let (world_width, world_height) = get_world_size(&world.map);
+    let thresholds = world.config.thresholds;
+    let mut dirty = DirtyRegions::new(world_width, world_height);
+    let mut seasons = SeasonCycle::new();
+    seasons.enabled = true;
+
+    for _ in 0..ticks {
+        let today = seasons.climate(climate);
+        let changed = reclassify(world, &thresholds, &today, &mut dirty);
+        dirty.clear();
+        seasons::update_yields(world, &today);
+
+        let total_yield: f32 = world
+            .layers
+            .scalars(seasons::YIELD_LAYER)
+            .map_or(0.0, |yields| yields.iter().sum());
+        println!(
+            "{:<24}{:>10} cells changed{:>14.1} yield",
+            seasons.describe(),
+            changed,
+            total_yield
+        );
+        seasons.next_day();
+    }
+}
+
+pub fn run(cli: Cli) -> Result<(), String> {
+    let command = cli.command.unwrap_or(Command::View {
+        source: WorldSource {
+            world: None,
+            seed: None,
+            sea_level: 0.0,
+            temperature_offset: 0.0,
+        },
+        goto: None,
+        infinite: false,
+        shader_dir: None,
+    });
+
+    match command {
+        Command::View {
+            source,
+            goto,
+            infinite,
+            shader_dir,
+        } => {
+            let seed = source.seed();
+            let world = if infinite {
+                if source.climate() != Climate::new() {
+                    return Err(String::from(
+                        "--sea-level and --temperature-offset don't apply to --infinite",
+                    ));
+                }
+                None
+            } else {
+                Some(source.load_seeded(seed)?)
+            };
+            if let (Some((x, y)), Some(world)) = (goto, &world) {
+                let (world_width, world_height) = get_world_size(&world.map);
+                if x < 0 || y < 0 || x >= world_width || y >= world_height {
+                    return Err(format!("cell {},{} is outside the world", x, y));
+                }
+            }
+            view(world, seed, source.climate(), goto, shader_dir)
+        }
+        Command::Generate {
+            seed,
+            width,
+            height,
+            out,
+        } => {
+            if width <= 0 || height <= 0 {
+                return Err(String::from("world size must be positive"));
+            }
+            let world = generate_timed(
+                seed.unwrap_or_else(generate_seed),
+                (width, height),
+                &WorldGenConfig::new(),
+            );
+            save::save_world(&world, &out)?;
+            println!("World saved to: {}", out.display());
+        }
+        Command::Render {
+            source,
+            camera,
+            width,
+            height,
+            out,
+        } => {
+            let world = source.load()?;
+            let world_size = get_world_size(&world.map);
+
+            let camera = match camera {
+                Some((x, y, zoom, rotation)) => Camera2D {
+                    offset: (width as f64 / 2.0, height as f64 / 2.0),
+                    position: (x, y),
+                    rotation,
+                    zoom,
+                },
+                None => Camera2D::fit_to_world(world_size, (width, height)),
+            };
+
+            export::export_render_png(&world.map, &camera, HEX_SIZE, width, height, &out)?;
+            println!("World rendered to: {}", out.display());
+        }
+        Command::Export { source, hex, out } => {
+            export::export_world(&source.load()?.map, &out, hex)?;
+            println!("World exported to: {}", out.display());
+        }
+        Command::Geojson { source, out } => {
+            geojson::export_geojson(&source.load()?, HEX_SIZE, &out)?;
+            println!("World exported to: {}", out.display());
+        }
+        Command::Stats { world } => print_stats(&save::load_world(&world)?.map),
+        Command::Simulate { source, ticks, out } => {
+            let mut world = source.load()?;
+            simulate(&mut world, &source.climate(), ticks);
+            if let Some(out) = out {
+                save::save_world(&world, &out)?;
+                println!("World saved to: {}", out.display());
+            }
+        }
+        Command::GpuCheck {
+            seed,
+            width,
+            height,
+        } => {
+            if width <= 0 || height <= 0 {
+                return Err(String::from("world size must be positive"));
+            }
+            gpu_check(seed, (width, height))?;
+        }
+    }
+
+    Ok(())
+}
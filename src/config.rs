@@ -0,0 +1,181 @@
+use noise::{Fbm, Perlin, RidgedMulti};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NoiseLayer {
+    pub octaves: usize,
+    pub frequency: f64,
+    pub lacunarity: f64,
+    pub persistence: f64,
+}
+
+impl NoiseLayer {
+    // Defaults of `noise::Fbm`.
+    pub fn fbm(octaves: usize, frequency: f64) -> NoiseLayer {
+        NoiseLayer {
+            octaves,
+            frequency,
+            lacunarity: Fbm::<Perlin>::DEFAULT_LACUNARITY,
+            persistence: Fbm::<Perlin>::DEFAULT_PERSISTENCE,
+        }
+    }
+}
+
+// Lower bounds of altitude, temperature, vegetation and bias for each cell type.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BiomeThresholds {
+    pub snow: f64,
+    pub high_mountain: f64,
+    pub medium_mountain: f64,
+    pub mountain: f64,
+    pub cliff_altitude: f64,
+    pub medium_cliff_bias: f64,
+    pub cliff_bias: f64,
+    pub land: f64,
+    pub sand_temperature: f64,
+    pub dirt_temperature: f64,
+    pub grass_temperature: f64,
+    pub tree_vegetation: f64,
+    pub tundra_temperature: f64,
+    pub beach_altitude: f64,
+    pub beach_bias: f64,
+    pub beach_temperature: f64,
+    pub shallow_water: f64,
+    pub ice_temperature: f64,
+    pub water: f64,
+    pub medium_water: f64,
+}
+
+impl BiomeThresholds {
+    // Water bands moved by `sea_level`; cliffs, mountains and snow never reach below the sea.
+    pub fn with_sea_level(self: &Self, sea_level: f64) -> BiomeThresholds {
+        let land = self.land + sea_level;
+
+        BiomeThresholds {
+            snow: self.snow.max(land),
+            high_mountain: self.high_mountain.max(land),
+            medium_mountain: self.medium_mountain.max(land),
+            mountain: self.mountain.max(land),
+            cliff_altitude: self.cliff_altitude.max(land),
+            land,
+            beach_altitude: self.beach_altitude + sea_level,
+            shallow_water: self.shallow_water + sea_level,
+            water: self.water + sea_level,
+            medium_water: self.medium_water + sea_level,
+            ..*self
+        }
+    }
+}
+
+// Global changes applied when classifying the generated fields, so coastlines and ice can move
+// without generating the noise again.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Climate {
+    // Added to the altitude of every water threshold.
+    pub sea_level: f64,
+    // Added to the temperature of every cell.
+    pub temperature_offset: f64,
+    // Added to the temperature of the top row, taken from the bottom row and faded out towards
+    // the middle one, so the two halves of the world have opposite seasons.
+    pub seasonal_temperature: f64,
+    // How far rivers spill over their banks, from 0 to 1.
+    pub river_swell: f64,
+}
+
+impl Default for Climate {
+    fn default() -> Climate {
+        Climate::new()
+    }
+}
+
+impl Climate {
+    pub fn new() -> Climate {
+        Climate {
+            sea_level: 0.0,
+            temperature_offset: 0.0,
+            seasonal_temperature: 0.0,
+            river_swell: 0.0,
+        }
+    }
+
+    // -1 at the bottom row, 1 at the top row.
+    pub fn latitude(y: usize, world_height: i32) -> f64 {
+        let half = (world_height - 1).max(1) as f64 / 2.0;
+        (half - y as f64) / half
+    }
+
+    pub fn temperature_offset_at(self: &Self, y: usize, world_height: i32) -> f64 {
+        self.temperature_offset + self.seasonal_temperature * Climate::latitude(y, world_height)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WorldGenConfig {
+    // World units per cell when sampling the noise.
+    pub scale: f64,
+    pub altitude: NoiseLayer,
+    pub temperature: NoiseLayer,
+    pub vegetation: NoiseLayer,
+    pub vegetation_roughness: usize,
+    pub vegetation_power: f64,
+    pub beach: NoiseLayer,
+    pub cliff: NoiseLayer,
+    pub cliff_attenuation: f64,
+    pub cliff_exponent: f64,
+    pub lake: NoiseLayer,
+    pub river_chance: f64,
+    pub river_climb: f64,
+    pub thresholds: BiomeThresholds,
+}
+
+impl Default for WorldGenConfig {
+    fn default() -> WorldGenConfig {
+        WorldGenConfig::new()
+    }
+}
+
+impl WorldGenConfig {
+    pub fn new() -> WorldGenConfig {
+        WorldGenConfig {
+            scale: 0.01,
+            altitude: NoiseLayer::fbm(10, 0.2),
+            temperature: NoiseLayer::fbm(6, 0.25),
+            vegetation: NoiseLayer::fbm(6, 0.3),
+            vegetation_roughness: 20,
+            vegetation_power: 2.0,
+            beach: NoiseLayer::fbm(6, 0.35),
+            cliff: NoiseLayer {
+                octaves: 10,
+                frequency: 1.0,
+                lacunarity: RidgedMulti::<Perlin>::DEFAULT_LACUNARITY,
+                persistence: 5.0,
+            },
+            cliff_attenuation: 0.8,
+            cliff_exponent: 0.1,
+            lake: NoiseLayer::fbm(10, 0.2),
+            river_chance: 0.0008,
+            river_climb: 0.04,
+            thresholds: BiomeThresholds {
+                snow: 0.85,
+                high_mountain: 0.81,
+                medium_mountain: 0.8,
+                mountain: 0.78,
+                cliff_altitude: 0.65,
+                medium_cliff_bias: 0.95,
+                cliff_bias: 0.8,
+                land: 0.6,
+                sand_temperature: 0.7,
+                dirt_temperature: 0.5,
+                grass_temperature: 0.4,
+                tree_vegetation: 0.6,
+                tundra_temperature: 0.3,
+                beach_altitude: 0.59,
+                beach_bias: 0.65,
+                beach_temperature: 0.4,
+                shallow_water: 0.56,
+                ice_temperature: 0.3,
+                water: 0.52,
+                medium_water: 0.48,
+            },
+        }
+    }
+}
@@ -0,0 +1,81 @@
+// Dirty cells closer than this are uploaded as one range to keep the number of buffer updates
+// low, rewriting the clean cells in between is cheaper than another call.
+pub const MERGE_GAP: usize = 256;
+
+pub struct DirtyRegions {
+    width: usize,
+    height: usize,
+    marked: Vec<bool>,
+    cells: Vec<usize>,
+}
+
+impl DirtyRegions {
+    pub fn new(world_width: i32, world_height: i32) -> DirtyRegions {
+        DirtyRegions {
+            width: world_width as usize,
+            height: world_height as usize,
+            marked: vec![false; (world_width * world_height) as usize],
+            cells: vec![],
+        }
+    }
+
+    pub fn mark(self: &mut Self, x: usize, y: usize) {
+        let i = x + y * self.width;
+        if !self.marked[i] {
+            self.marked[i] = true;
+            self.cells.push(i);
+        }
+    }
+
+    pub fn mark_all(self: &mut Self) {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                self.mark(x, y);
+            }
+        }
+    }
+
+    pub fn is_empty(self: &Self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn clear(self: &mut Self) {
+        for i in self.cells.drain(..) {
+            self.marked[i] = false;
+        }
+    }
+
+    pub fn cells(self: &Self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.cells.iter().map(|i| (i % self.width, i / self.width))
+    }
+
+    // Dirty cell index ranges in row-major order, end exclusive, with nearby cells merged.
+    pub fn ranges(self: &Self) -> Vec<(usize, usize)> {
+        let mut cells = self.cells.clone();
+        cells.sort_unstable();
+
+        let mut ranges: Vec<(usize, usize)> = vec![];
+        for i in cells {
+            match ranges.last_mut() {
+                Some(last) if i <= last.1 + MERGE_GAP => last.1 = i + 1,
+                _ => ranges.push((i, i + 1)),
+            }
+        }
+
+        ranges
+    }
+
+    // The same regions on a grid with half the width and height, used for the LOD levels.
+    pub fn downsample(self: &Self) -> DirtyRegions {
+        let mut downsampled = DirtyRegions::new(
+            self.width.div_ceil(2) as i32,
+            self.height.div_ceil(2) as i32,
+        );
+
+        for (x, y) in self.cells() {
+            downsampled.mark(x / 2, y / 2);
+        }
+
+        downsampled
+    }
+}
@@ -0,0 +1,40 @@
+use civilizations::{EdgeMode, Overlay, lighting::Lighting};
+
+pub struct Display {
+    pub overlay: Overlay,
+    // Index into the world's layers shown by `Overlay::Layer`.
+    pub layer: usize,
+    pub grid: bool,
+    pub edge_mode: EdgeMode,
+    pub lighting: Lighting,
+    pub timing: bool,
+    pub panels: bool,
+}
+
+impl Display {
+    pub fn new() -> Display {
+        Display {
+            overlay: Overlay::Biome,
+            layer: 0,
+            grid: false,
+            edge_mode: EdgeMode::None,
+            lighting: Lighting::new(),
+            timing: false,
+            panels: true,
+        }
+    }
+
+    pub fn describe(self: &Self) -> String {
+        let mut description = String::new();
+        if self.overlay != Overlay::Biome {
+            description += &format!(" [{:?}]", self.overlay);
+        }
+        if self.edge_mode != EdgeMode::None {
+            description += &format!(" [{:?}]", self.edge_mode);
+        }
+        if self.lighting.hillshade {
+            description += &format!(" [{}]", self.lighting.describe());
+        }
+        description
+    }
+}
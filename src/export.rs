@@ -0,0 +1,161 @@
+use std::{fs::File, io::BufWriter, path::Path};
+
+use crate::{
+    Camera2D, Cell, EdgeMode, generate_world_colors, generate_world_fields, get_world_size, hex,
+    lighting::Lighting, lod, raster,
+};
+
+// 1 GiB of RGBA; anything larger is almost certainly a mistyped size.
+const MAX_EXPORT_PIXELS: usize = 1 << 28;
+
+fn write_png(
+    path: &Path,
+    width: u32,
+    height: u32,
+    color_type: png::ColorType,
+    bit_depth: png::BitDepth,
+    data: &[u8],
+) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(color_type);
+    encoder.set_depth(bit_depth);
+
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(data).map_err(|e| e.to_string())
+}
+
+fn color_to_rgba(color: (f64, f64, f64)) -> [u8; 4] {
+    [
+        color.0.round() as u8,
+        color.1.round() as u8,
+        color.2.round() as u8,
+        255,
+    ]
+}
+
+pub fn export_colors_png(map: &[Vec<Cell>], path: &Path) -> Result<(), String> {
+    let (world_width, world_height) = get_world_size(map);
+
+    let mut data: Vec<u8> = vec![];
+
+    for row in map {
+        for cell in row {
+            data.extend_from_slice(&color_to_rgba(cell.get_color()));
+        }
+    }
+
+    write_png(
+        path,
+        world_width as u32,
+        world_height as u32,
+        png::ColorType::Rgba,
+        png::BitDepth::Eight,
+        &data,
+    )
+}
+
+// Renders `map` the way the viewer shows it with its default display options.
+pub fn export_render_png(
+    map: &[Vec<Cell>],
+    camera: &Camera2D,
+    size: f32,
+    width: u32,
+    height: u32,
+    path: &Path,
+) -> Result<(), String> {
+    (width as usize)
+        .checked_mul(height as usize)
+        .filter(|pixels| *pixels <= MAX_EXPORT_PIXELS)
+        .ok_or(format!("{}x{} image is too large", width, height))?;
+
+    let world_size = get_world_size(map);
+    let colors = generate_world_colors(map);
+    let world = raster::RasterWorld {
+        size: world_size,
+        fields: &generate_world_fields(map),
+        lod: &lod::generate_color_pyramid(&colors, world_size.0, world_size.1),
+        colors: &colors,
+    };
+    let view = raster::RasterView {
+        camera,
+        size,
+        width,
+        height,
+        clear_color: [0, 0, 0, 0],
+        lighting: &Lighting::new(),
+        grid: false,
+        edge_mode: EdgeMode::None,
+    };
+    let data = raster::render(&world, &view);
+
+    write_png(
+        path,
+        width,
+        height,
+        png::ColorType::Rgba,
+        png::BitDepth::Eight,
+        &data,
+    )
+}
+
+pub fn export_hex_colors_png(map: &[Vec<Cell>], path: &Path, size: f32) -> Result<(), String> {
+    let world_size = get_world_size(map);
+    let (min_x, min_y, max_x, max_y) = hex::hex_world_bounds(world_size.0, world_size.1, size);
+
+    let camera = Camera2D {
+        offset: (0.0, 0.0),
+        position: (min_x as f64, min_y as f64),
+        rotation: 0.0,
+        zoom: 1.0,
+    };
+
+    export_render_png(
+        map,
+        &camera,
+        size,
+        (max_x - min_x).ceil() as u32,
+        (max_y - min_y).ceil() as u32,
+        path,
+    )
+}
+
+pub fn export_field_png(
+    map: &[Vec<Cell>],
+    path: &Path,
+    field: fn(&Cell) -> f64,
+) -> Result<(), String> {
+    let (world_width, world_height) = get_world_size(map);
+
+    let mut data: Vec<u8> = vec![];
+
+    for row in map {
+        for cell in row {
+            let value = (field(cell).clamp(0.0, 1.0) * u16::MAX as f64).round() as u16;
+            data.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+
+    write_png(
+        path,
+        world_width as u32,
+        world_height as u32,
+        png::ColorType::Grayscale,
+        png::BitDepth::Sixteen,
+        &data,
+    )
+}
+
+pub fn export_world(map: &[Vec<Cell>], dir: &Path, hex_size: Option<f32>) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+
+    match hex_size {
+        Some(size) => export_hex_colors_png(map, &dir.join("biome.png"), size)?,
+        None => export_colors_png(map, &dir.join("biome.png"))?,
+    }
+    export_field_png(map, &dir.join("altitude.png"), |cell| cell.altitude)?;
+    export_field_png(map, &dir.join("temperature.png"), |cell| cell.temperature)?;
+    export_field_png(map, &dir.join("vegetation.png"), |cell| cell.vegetation)?;
+
+    Ok(())
+}
@@ -0,0 +1,171 @@
+pub const GLYPH_WIDTH: usize = 5;
+pub const GLYPH_HEIGHT: usize = 7;
+
+const FIRST_CHAR: u8 = b' ';
+const ATLAS_COLUMNS: usize = 16;
+const ATLAS_ROWS: usize = 6;
+const CELL_WIDTH: usize = GLYPH_WIDTH + 1;
+const CELL_HEIGHT: usize = GLYPH_HEIGHT + 1;
+
+// Printable ASCII from ' ' to '~', one row per byte with the leftmost pixel in bit 4.
+const GLYPHS: [[u8; GLYPH_HEIGHT]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],
+    [0x0a, 0x0a, 0x0a, 0x00, 0x00, 0x00, 0x00],
+    [0x0a, 0x0a, 0x1f, 0x0a, 0x1f, 0x0a, 0x0a],
+    [0x04, 0x0f, 0x14, 0x0e, 0x05, 0x1e, 0x04],
+    [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],
+    [0x0c, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0d],
+    [0x04, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00],
+    [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+    [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+    [0x00, 0x04, 0x15, 0x0e, 0x15, 0x04, 0x00],
+    [0x00, 0x04, 0x04, 0x1f, 0x04, 0x04, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x0c, 0x04, 0x08],
+    [0x00, 0x00, 0x00, 0x1f, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0c, 0x0c],
+    [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+    [0x0e, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0e],
+    [0x04, 0x0c, 0x04, 0x04, 0x04, 0x04, 0x0e],
+    [0x0e, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1f],
+    [0x1f, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0e],
+    [0x02, 0x06, 0x0a, 0x12, 0x1f, 0x02, 0x02],
+    [0x1f, 0x10, 0x1e, 0x01, 0x01, 0x11, 0x0e],
+    [0x06, 0x08, 0x10, 0x1e, 0x11, 0x11, 0x0e],
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+    [0x0e, 0x11, 0x11, 0x0e, 0x11, 0x11, 0x0e],
+    [0x0e, 0x11, 0x11, 0x0f, 0x01, 0x02, 0x0c],
+    [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x0c, 0x00],
+    [0x00, 0x0c, 0x0c, 0x00, 0x0c, 0x04, 0x08],
+    [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02],
+    [0x00, 0x00, 0x1f, 0x00, 0x1f, 0x00, 0x00],
+    [0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08],
+    [0x0e, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    [0x0e, 0x11, 0x01, 0x0d, 0x15, 0x15, 0x0e],
+    [0x0e, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11],
+    [0x1e, 0x11, 0x11, 0x1e, 0x11, 0x11, 0x1e],
+    [0x0e, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0e],
+    [0x1c, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1c],
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x1f],
+    [0x1f, 0x10, 0x10, 0x1e, 0x10, 0x10, 0x10],
+    [0x0e, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0f],
+    [0x11, 0x11, 0x11, 0x1f, 0x11, 0x11, 0x11],
+    [0x0e, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e],
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0c],
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1f],
+    [0x11, 0x1b, 0x15, 0x15, 0x11, 0x11, 0x11],
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+    [0x0e, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+    [0x1e, 0x11, 0x11, 0x1e, 0x10, 0x10, 0x10],
+    [0x0e, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0d],
+    [0x1e, 0x11, 0x11, 0x1e, 0x14, 0x12, 0x11],
+    [0x0f, 0x10, 0x10, 0x0e, 0x01, 0x01, 0x1e],
+    [0x1f, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0e],
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0a, 0x04],
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0a],
+    [0x11, 0x11, 0x0a, 0x04, 0x0a, 0x11, 0x11],
+    [0x11, 0x11, 0x0a, 0x04, 0x04, 0x04, 0x04],
+    [0x1f, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1f],
+    [0x0e, 0x08, 0x08, 0x08, 0x08, 0x08, 0x0e],
+    [0x00, 0x10, 0x08, 0x04, 0x02, 0x01, 0x00],
+    [0x0e, 0x02, 0x02, 0x02, 0x02, 0x02, 0x0e],
+    [0x04, 0x0a, 0x11, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1f],
+    [0x08, 0x04, 0x02, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x0e, 0x01, 0x0f, 0x11, 0x0f],
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x1e],
+    [0x00, 0x00, 0x0e, 0x10, 0x10, 0x11, 0x0e],
+    [0x01, 0x01, 0x0d, 0x13, 0x11, 0x11, 0x0f],
+    [0x00, 0x00, 0x0e, 0x11, 0x1f, 0x10, 0x0e],
+    [0x06, 0x09, 0x08, 0x1c, 0x08, 0x08, 0x08],
+    [0x00, 0x0f, 0x11, 0x11, 0x0f, 0x01, 0x0e],
+    [0x10, 0x10, 0x16, 0x19, 0x11, 0x11, 0x11],
+    [0x04, 0x00, 0x0c, 0x04, 0x04, 0x04, 0x0e],
+    [0x02, 0x00, 0x06, 0x02, 0x02, 0x12, 0x0c],
+    [0x10, 0x10, 0x12, 0x14, 0x18, 0x14, 0x12],
+    [0x0c, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0e],
+    [0x00, 0x00, 0x1a, 0x15, 0x15, 0x11, 0x11],
+    [0x00, 0x00, 0x16, 0x19, 0x11, 0x11, 0x11],
+    [0x00, 0x00, 0x0e, 0x11, 0x11, 0x11, 0x0e],
+    [0x00, 0x00, 0x1e, 0x11, 0x1e, 0x10, 0x10],
+    [0x00, 0x00, 0x0d, 0x13, 0x0f, 0x01, 0x01],
+    [0x00, 0x00, 0x16, 0x19, 0x10, 0x10, 0x10],
+    [0x00, 0x00, 0x0e, 0x10, 0x0e, 0x01, 0x1e],
+    [0x08, 0x08, 0x1c, 0x08, 0x08, 0x09, 0x06],
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x13, 0x0d],
+    [0x00, 0x00, 0x11, 0x11, 0x11, 0x0a, 0x04],
+    [0x00, 0x00, 0x11, 0x11, 0x15, 0x15, 0x0a],
+    [0x00, 0x00, 0x11, 0x0a, 0x04, 0x0a, 0x11],
+    [0x00, 0x00, 0x11, 0x11, 0x0f, 0x01, 0x0e],
+    [0x00, 0x00, 0x1f, 0x02, 0x04, 0x08, 0x1f],
+    [0x02, 0x04, 0x04, 0x08, 0x04, 0x04, 0x02],
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+    [0x08, 0x04, 0x04, 0x02, 0x04, 0x04, 0x08],
+    [0x00, 0x00, 0x08, 0x15, 0x02, 0x00, 0x00],
+];
+
+// The cell after the last glyph is filled so solid quads can share the font texture.
+const SOLID_INDEX: usize = GLYPHS.len();
+
+pub const ATLAS_WIDTH: usize = ATLAS_COLUMNS * CELL_WIDTH;
+pub const ATLAS_HEIGHT: usize = ATLAS_ROWS * CELL_HEIGHT;
+
+// One byte of coverage per pixel, glyphs laid out row by row with one pixel of padding.
+pub fn atlas() -> Vec<u8> {
+    let mut pixels = vec![0u8; ATLAS_WIDTH * ATLAS_HEIGHT];
+
+    for (index, glyph) in GLYPHS.iter().enumerate() {
+        let (cell_x, cell_y) = cell_origin(index);
+        for (row, bits) in glyph.iter().enumerate() {
+            for column in 0..GLYPH_WIDTH {
+                if bits & (1 << (GLYPH_WIDTH - 1 - column)) != 0 {
+                    pixels[(cell_y + row) * ATLAS_WIDTH + cell_x + column] = 255;
+                }
+            }
+        }
+    }
+
+    let (solid_x, solid_y) = cell_origin(SOLID_INDEX);
+    for row in 0..CELL_HEIGHT {
+        for column in 0..CELL_WIDTH {
+            pixels[(solid_y + row) * ATLAS_WIDTH + solid_x + column] = 255;
+        }
+    }
+
+    pixels
+}
+
+fn cell_origin(index: usize) -> (usize, usize) {
+    (
+        index % ATLAS_COLUMNS * CELL_WIDTH,
+        index / ATLAS_COLUMNS * CELL_HEIGHT,
+    )
+}
+
+fn cell_uv(index: usize, width: usize, height: usize) -> (f32, f32, f32, f32) {
+    let (x, y) = cell_origin(index);
+    (
+        x as f32 / ATLAS_WIDTH as f32,
+        y as f32 / ATLAS_HEIGHT as f32,
+        (x + width) as f32 / ATLAS_WIDTH as f32,
+        (y + height) as f32 / ATLAS_HEIGHT as f32,
+    )
+}
+
+// Texture coordinates of a character as `(u0, v0, u1, v1)`, unknown characters show as '?'.
+pub fn glyph_uv(c: char) -> (f32, f32, f32, f32) {
+    let index = match c {
+        ' '..='~' => c as usize - FIRST_CHAR as usize,
+        _ => '?' as usize - FIRST_CHAR as usize,
+    };
+    cell_uv(index, GLYPH_WIDTH, GLYPH_HEIGHT)
+}
+
+// Texture coordinates inside the filled cell, away from its edges.
+pub fn solid_uv() -> (f32, f32, f32, f32) {
+    let (u0, v0, u1, v1) = cell_uv(SOLID_INDEX, CELL_WIDTH, CELL_HEIGHT);
+    let (center_u, center_v) = ((u0 + u1) / 2.0, (v0 + v1) / 2.0);
+    (center_u, center_v, center_u, center_v)
+}
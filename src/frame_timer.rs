@@ -0,0 +1,58 @@
+use std::time::Instant;
+
+pub const FRAME_SAMPLES: usize = 120;
+
+pub struct FrameTimer {
+    samples: [f32; FRAME_SAMPLES],
+    next: usize,
+    count: usize,
+    last_frame: Instant,
+}
+
+impl FrameTimer {
+    pub fn new() -> FrameTimer {
+        FrameTimer {
+            samples: [0.0; FRAME_SAMPLES],
+            next: 0,
+            count: 0,
+            last_frame: Instant::now(),
+        }
+    }
+
+    // Records the time since the previous call and returns it in seconds.
+    pub fn tick(self: &mut Self) -> f64 {
+        let dt = self.last_frame.elapsed().as_secs_f64();
+        self.last_frame = Instant::now();
+
+        self.samples[self.next] = (dt * 1000.0) as f32;
+        self.next = (self.next + 1) % FRAME_SAMPLES;
+        self.count = (self.count + 1).min(FRAME_SAMPLES);
+
+        dt
+    }
+
+    pub fn count(self: &Self) -> usize {
+        self.count
+    }
+
+    // Frame times in milliseconds, oldest first.
+    pub fn samples(self: &Self) -> [f32; FRAME_SAMPLES] {
+        let mut samples = [0.0; FRAME_SAMPLES];
+        for (i, sample) in samples.iter_mut().enumerate() {
+            *sample = self.samples[(self.next + i) % FRAME_SAMPLES];
+        }
+        samples
+    }
+
+    pub fn describe(self: &Self) -> String {
+        let samples = &self.samples()[FRAME_SAMPLES - self.count..];
+        let average = samples.iter().sum::<f32>() / samples.len().max(1) as f32;
+        let max = samples.iter().copied().fold(0.0, f32::max);
+        format!(
+            "frame {:.2} ms avg, {:.2} ms max, {:.0} fps",
+            average,
+            max,
+            1000.0 / average.max(0.001)
+        )
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+    time::Instant,
+};
+
+use civilizations::{
+    Cell, Climate, World, WorldGenConfig, generate_world_with_config, seasons::SeasonCycle,
+    world::finish_world,
+};
+
+pub fn generate_timed(seed: u64, world_size: (i32, i32), config: &WorldGenConfig) -> World {
+    let time = Instant::now();
+    let world = generate_world_with_config(seed, world_size.0, world_size.1, config);
+    println!("World generated in: {}s", time.elapsed().as_secs_f32());
+    world
+}
+
+// Regenerates the world on a background thread so the viewer keeps drawing the current one.
+pub struct Generator {
+    pub config: WorldGenConfig,
+    // Applied to the current world without generating it again, with the season on top.
+    pub climate: Climate,
+    pub seasons: SeasonCycle,
+    pub seed: u64,
+    pub auto: bool,
+    pub use_gpu: bool,
+    pub gpu_available: bool,
+    // Set for the chunked world, which regenerates by restarting its chunks.
+    pub streaming: bool,
+    world_size: (i32, i32),
+    pending: bool,
+    requested: bool,
+    reclassify: bool,
+    job: Option<Receiver<World>>,
+    // Set while the job only traces the rivers of cells the GPU already uploaded.
+    tracing_rivers: bool,
+}
+
+impl Generator {
+    pub fn new(seed: u64, world_size: (i32, i32)) -> Generator {
+        Generator {
+            config: WorldGenConfig::new(),
+            climate: Climate::new(),
+            seasons: SeasonCycle::new(),
+            seed,
+            auto: false,
+            use_gpu: false,
+            gpu_available: false,
+            streaming: false,
+            world_size,
+            pending: false,
+            requested: false,
+            reclassify: false,
+            job: None,
+            tracing_rivers: false,
+        }
+    }
+
+    pub fn is_running(self: &Self) -> bool {
+        self.job.is_some()
+    }
+
+    // Starts a new generation, or queues one when a generation is already running.
+    pub fn regenerate(self: &mut Self) {
+        if self.use_gpu || self.streaming {
+            // GPU and chunked generation run from the main thread, which picks this up with
+            // `take_request`; a running CPU generation is dropped.
+            self.job = None;
+            self.tracing_rivers = false;
+            self.pending = false;
+            self.requested = true;
+            return;
+        }
+        if self.job.is_some() {
+            self.pending = true;
+            return;
+        }
+        self.pending = false;
+        self.tracing_rivers = false;
+
+        let (sender, receiver) = mpsc::channel();
+        let (seed, config, world_size) = (self.seed, self.config, self.world_size);
+        thread::spawn(move || {
+            let _ = sender.send(generate_timed(seed, world_size, &config));
+        });
+        self.job = Some(receiver);
+    }
+
+    // Finishes cells generated on the GPU on a background thread, `poll` returns the world.
+    pub fn trace_rivers(self: &mut Self, map: Vec<Vec<Cell>>) {
+        let (sender, receiver) = mpsc::channel();
+        let (seed, config) = (self.seed, self.config);
+        thread::spawn(move || {
+            let _ = sender.send(finish_world(map, seed, &config));
+        });
+        self.job = Some(receiver);
+        self.tracing_rivers = true;
+    }
+
+    pub fn changed(self: &mut Self) {
+        if self.auto {
+            self.regenerate();
+        }
+    }
+
+    pub fn take_request(self: &mut Self) -> bool {
+        std::mem::take(&mut self.requested)
+    }
+
+    // The climate of today's season.
+    pub fn current_climate(self: &Self) -> Climate {
+        self.seasons.climate(&self.climate)
+    }
+
+    pub fn climate_changed(self: &mut Self) {
+        self.reclassify = true;
+    }
+
+    pub fn take_reclassify(self: &mut Self) -> bool {
+        std::mem::take(&mut self.reclassify)
+    }
+
+    // The finished world, and whether it only adds rivers to the cells in the buffers.
+    pub fn poll(self: &mut Self) -> Option<(World, bool)> {
+        let result = match self.job.as_ref()?.try_recv() {
+            Ok(world) => Some((world, self.tracing_rivers)),
+            Err(TryRecvError::Empty) => return None,
+            Err(TryRecvError::Disconnected) => {
+                eprintln!("World generation failed");
+                None
+            }
+        };
+
+        self.job = None;
+        self.tracing_rivers = false;
+        if self.pending {
+            self.regenerate();
+        }
+        result
+    }
+}
@@ -0,0 +1,281 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{BufWriter, Write},
+    path::Path,
+};
+
+use crate::{Cell, CellType, World, get_neighbors, get_world_size, hex};
+
+// Hex corners on an integer lattice: x in steps of sqrt(3) / 2 * size, y in steps of size / 2.
+// Each entry is the edge from `corner` to the next corner clockwise on screen and the
+// doublewidth offset of the neighbor sharing that edge.
+type Corner = (i64, i64);
+
+const EDGES: [(Corner, Corner, (i64, i64)); 6] = [
+    ((0, -2), (1, -1), (1, -1)),
+    ((1, -1), (1, 1), (2, 0)),
+    ((1, 1), (0, 2), (1, 1)),
+    ((0, 2), (-1, 1), (-1, 1)),
+    ((-1, 1), (-1, -1), (-2, 0)),
+    ((-1, -1), (0, -2), (-1, -1)),
+];
+
+struct Region {
+    cell_type: CellType,
+    cells: Vec<(usize, usize)>,
+}
+
+fn find_regions(map: &[Vec<Cell>]) -> (Vec<Region>, Vec<Vec<usize>>) {
+    let (world_width, world_height) = get_world_size(map);
+
+    let mut labels = vec![vec![usize::MAX; world_width as usize]; world_height as usize];
+    let mut regions = vec![];
+
+    for y in 0..world_height as usize {
+        for x in 0..world_width as usize {
+            if labels[y][x] != usize::MAX || map[y][x].cell_type == CellType::River {
+                continue;
+            }
+
+            let cell_type = map[y][x].cell_type;
+            let label = regions.len();
+            let mut cells = vec![];
+            let mut stack = vec![(x * 2 + y % 2, y)];
+            labels[y][x] = label;
+
+            while let Some((cx, cy)) = stack.pop() {
+                cells.push((cx, cy));
+                for (nx, ny) in get_neighbors(map, cx, cy) {
+                    if labels[ny][nx / 2] == usize::MAX && map[ny][nx / 2].cell_type == cell_type {
+                        labels[ny][nx / 2] = label;
+                        stack.push((nx, ny));
+                    }
+                }
+            }
+
+            regions.push(Region { cell_type, cells });
+        }
+    }
+
+    (regions, labels)
+}
+
+fn ring_area(ring: &[Corner]) -> i64 {
+    let mut area = 0;
+    for i in 0..ring.len() {
+        let (x0, y0) = ring[i];
+        let (x1, y1) = ring[(i + 1) % ring.len()];
+        area += x0 * y1 - x1 * y0;
+    }
+    area
+}
+
+fn trace_rings(region: &Region, label: usize, labels: &[Vec<usize>]) -> Vec<Vec<Corner>> {
+    let world_height = labels.len() as i64;
+    let world_width = labels[0].len() as i64 * 2;
+
+    let mut edges: HashMap<Corner, Corner> = HashMap::new();
+
+    for &(x, y) in &region.cells {
+        let (cx, cy) = (x as i64, y as i64 * 3);
+        for (from, to, neighbor) in EDGES {
+            let nx = x as i64 + neighbor.0;
+            let ny = y as i64 + neighbor.1;
+            let inside = nx >= 0
+                && ny >= 0
+                && nx < world_width
+                && ny < world_height
+                && labels[ny as usize][nx as usize / 2] == label;
+            if !inside {
+                edges.insert((cx + to.0, cy + to.1), (cx + from.0, cy + from.1));
+            }
+        }
+    }
+
+    let mut rings = vec![];
+    while let Some(&start) = edges.keys().next() {
+        let mut ring = vec![];
+        let mut corner = start;
+        while let Some(next) = edges.remove(&corner) {
+            ring.push(corner);
+            corner = next;
+        }
+        rings.push(ring);
+    }
+
+    // Edges are walked against the clockwise corner order, so the exterior ring comes out
+    // counterclockwise and holes clockwise, as GeoJSON expects.
+    rings.sort_by_key(|ring| -ring_area(ring).abs());
+    rings
+}
+
+fn corner_to_point(corner: Corner, size: f32) -> (f64, f64) {
+    let size = size as f64;
+    (
+        corner.0 as f64 * size * 3.0_f64.sqrt() / 2.0,
+        -(corner.1 as f64) * size / 2.0,
+    )
+}
+
+fn format_ring(ring: &[Corner], size: f32) -> String {
+    let mut points: Vec<String> = ring
+        .iter()
+        .map(|corner| {
+            let (x, y) = corner_to_point(*corner, size);
+            format!("[{:.3},{:.3}]", x, y)
+        })
+        .collect();
+    points.push(points[0].clone());
+    format!("[{}]", points.join(","))
+}
+
+pub fn export_geojson(world: &World, size: f32, path: &Path) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut writer = BufWriter::new(file);
+
+    let map = &world.map;
+    let (regions, labels) = find_regions(map);
+
+    let mut features: Vec<String> = vec![];
+
+    for (label, region) in regions.iter().enumerate() {
+        let rings: Vec<String> = trace_rings(region, label, &labels)
+            .iter()
+            .map(|ring| format_ring(ring, size))
+            .collect();
+
+        let mean_altitude = region
+            .cells
+            .iter()
+            .map(|(x, y)| map[*y][*x / 2].altitude)
+            .sum::<f64>()
+            / region.cells.len() as f64;
+
+        features.push(format!(
+            "{{\"type\":\"Feature\",\"properties\":{{\"type\":\"{:?}\",\"mean_altitude\":{:.4},\"cells\":{}}},\"geometry\":{{\"type\":\"Polygon\",\"coordinates\":[{}]}}}}",
+            region.cell_type,
+            mean_altitude,
+            region.cells.len(),
+            rings.join(",")
+        ));
+    }
+
+    for river in world.rivers.iter().filter(|river| river.len() > 1) {
+        let points: Vec<String> = river
+            .iter()
+            .map(|(x, y)| {
+                let (px, py) = hex::doublewidth_to_pixel((*x as f32, *y as f32), size);
+                format!("[{:.3},{:.3}]", px, -py)
+            })
+            .collect();
+
+        let mean_altitude = river
+            .iter()
+            .map(|(x, y)| map[*y][*x / 2].altitude)
+            .sum::<f64>()
+            / river.len() as f64;
+
+        features.push(format!(
+            "{{\"type\":\"Feature\",\"properties\":{{\"type\":\"River\",\"mean_altitude\":{:.4},\"cells\":{}}},\"geometry\":{{\"type\":\"LineString\",\"coordinates\":[{}]}}}}",
+            mean_altitude,
+            river.len(),
+            points.join(",")
+        ));
+    }
+
+    write!(
+        writer,
+        "{{\"type\":\"FeatureCollection\",\"features\":[\n{}\n]}}\n",
+        features.join(",\n")
+    )
+    .map_err(|e| e.to_string())?;
+
+    writer.flush().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Layers, WorldGenConfig, chunks::placeholder_cell};
+
+    // A grass field with a single lake cell in the middle and a short river along the top.
+    fn lake_world() -> World {
+        let mut map = vec![vec![placeholder_cell(); 7]; 7];
+        for row in map.iter_mut() {
+            for cell in row.iter_mut() {
+                cell.cell_type = CellType::Grass;
+            }
+        }
+        map[3][3].cell_type = CellType::Lake;
+        map[0][1].cell_type = CellType::River;
+        map[0][2].cell_type = CellType::River;
+
+        World {
+            map,
+            rivers: vec![vec![(2, 0), (4, 0)]],
+            layers: Layers::new(7, 7),
+            config: WorldGenConfig::new(),
+        }
+    }
+
+    #[test]
+    fn rings_are_closed() {
+        let world = lake_world();
+        let (regions, labels) = find_regions(&world.map);
+
+        for (label, region) in regions.iter().enumerate() {
+            for ring in trace_rings(region, label, &labels) {
+                let formatted = format_ring(&ring, 10.0);
+                let points: Vec<&str> = formatted
+                    .trim_start_matches("[[")
+                    .trim_end_matches("]]")
+                    .split("],[")
+                    .collect();
+                assert_eq!(points.len(), ring.len() + 1);
+                assert_eq!(points.first(), points.last());
+            }
+        }
+    }
+
+    #[test]
+    fn a_region_around_a_hole_has_an_interior_ring() {
+        let world = lake_world();
+        let (regions, labels) = find_regions(&world.map);
+
+        let grass = regions
+            .iter()
+            .position(|region| region.cell_type == CellType::Grass)
+            .unwrap();
+        let lake = regions
+            .iter()
+            .position(|region| region.cell_type == CellType::Lake)
+            .unwrap();
+
+        let grass_rings = trace_rings(&regions[grass], grass, &labels);
+        assert_eq!(grass_rings.len(), 2);
+        assert!(ring_area(&grass_rings[0]).abs() > ring_area(&grass_rings[1]).abs());
+        // The exterior winds the other way from its hole.
+        assert!(ring_area(&grass_rings[0]).signum() != ring_area(&grass_rings[1]).signum());
+
+        let lake_rings = trace_rings(&regions[lake], lake, &labels);
+        assert_eq!(lake_rings.len(), 1);
+        assert_eq!(lake_rings[0].len(), 6);
+    }
+
+    #[test]
+    fn rivers_are_exported_as_line_strings() {
+        let world = lake_world();
+        let path = std::env::temp_dir().join(format!(
+            "civilizations-{}-rivers.geojson",
+            std::process::id()
+        ));
+        export_geojson(&world, 10.0, &path).unwrap();
+        let geojson = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(geojson.matches("\"type\":\"LineString\"").count(), 1);
+        assert_eq!(geojson.matches("\"type\":\"Polygon\"").count(), 2);
+        assert!(geojson.contains("\"coordinates\":[[17.321,-0.000],[34.641,-0.000]]"));
+    }
+}
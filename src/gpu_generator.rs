@@ -0,0 +1,330 @@
+use civilizations::{
+    Cell, CellType, Layers, World, WorldGenConfig,
+    config::{BiomeThresholds, NoiseLayer},
+};
+use gl::types::{GLsizeiptr, GLuint, GLvoid};
+use noise::{
+    Fbm, Perlin, RidgedMulti,
+    permutationtable::{NoiseHasher, PermutationTable},
+};
+
+use crate::{
+    renderer::{create_compute_program, uniform_location},
+    world_buffers::WorldBuffers,
+};
+
+pub const GENERATE_COMPUTE_SRC: &str = include_str!("../assets/generate_compute.glsl");
+
+const WORK_GROUP_SIZE: GLuint = 16;
+const TABLE_SIZE: usize = 256;
+
+// Altitude, temperature, vegetation, beach, cliff, lake and the two vegetation distortions,
+// in the order of the `LAYER_*` constants of the shader.
+const LAYER_COUNT: usize = 8;
+const THRESHOLD_COUNT: usize = 20;
+
+// Mirrors `GeneratedCell` of the shader with std430 layout.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct GeneratedCell {
+    altitude: f64,
+    relative_altitude: f64,
+    temperature: f64,
+    vegetation: f64,
+    beach_bias: f64,
+    cliff_bias: f64,
+    lake_bias: f64,
+    cell_type: u32,
+    padding: u32,
+}
+
+const EMPTY_CELL: GeneratedCell = GeneratedCell {
+    altitude: 0.0,
+    relative_altitude: 0.0,
+    temperature: 0.0,
+    vegetation: 0.0,
+    beach_bias: 0.0,
+    cliff_bias: 0.0,
+    lake_bias: 0.0,
+    cell_type: 0,
+    padding: 0,
+};
+
+// One fractal noise function: its Perlin sources use seeds `seed..seed + octaves`.
+struct GpuLayer {
+    seed: u32,
+    octaves: usize,
+    frequency: f64,
+    lacunarity: f64,
+    persistence: f64,
+    scale_factor: f64,
+}
+
+impl GpuLayer {
+    // Same octave clamping and scale factor as `noise::Fbm`.
+    fn fbm(seed: u32, layer: &NoiseLayer) -> GpuLayer {
+        let octaves = layer.octaves.clamp(1, Fbm::<Perlin>::MAX_OCTAVES);
+        let denom = (1..=octaves).fold(0.0, |acc, x| acc + layer.persistence.powi(x as i32));
+
+        GpuLayer {
+            seed,
+            octaves,
+            frequency: layer.frequency,
+            lacunarity: layer.lacunarity,
+            persistence: layer.persistence,
+            scale_factor: 1.0 / denom,
+        }
+    }
+
+    // Same octave clamping and scale factor as `noise::RidgedMulti`.
+    fn ridged_multi(seed: u32, layer: &NoiseLayer, attenuation: f64) -> GpuLayer {
+        let octaves = layer.octaves.clamp(1, RidgedMulti::<Perlin>::MAX_OCTAVES);
+
+        let mut denom = 1.0;
+        let mut amplitude = 1.0;
+        let mut signal = 1.0;
+        for x in 1..=octaves {
+            amplitude *= layer.persistence;
+            let weight: f64 = (signal / attenuation.powi(x as i32)).clamp(0.0, 1.0);
+            signal = weight * amplitude;
+            denom += signal;
+        }
+
+        GpuLayer {
+            seed,
+            octaves,
+            frequency: layer.frequency,
+            lacunarity: layer.lacunarity,
+            persistence: layer.persistence,
+            scale_factor: 2.0 / denom,
+        }
+    }
+}
+
+// The noise functions of `WorldNoise::new`.
+fn layers(seed: u64, config: &WorldGenConfig) -> [GpuLayer; LAYER_COUNT] {
+    let seed = seed as u32;
+    // `Turbulence` distorts with default Fbms seeded 0 and 1 with `roughness` octaves.
+    let distort = NoiseLayer::fbm(config.vegetation_roughness, 1.0);
+
+    [
+        GpuLayer::fbm(seed, &config.altitude),
+        GpuLayer::fbm(seed.wrapping_add(10), &config.temperature),
+        GpuLayer::fbm(seed.wrapping_add(20), &config.vegetation),
+        GpuLayer::fbm(seed.wrapping_add(30), &config.beach),
+        GpuLayer::ridged_multi(
+            seed.wrapping_add(40),
+            &config.cliff,
+            config.cliff_attenuation,
+        ),
+        GpuLayer::fbm(seed.wrapping_add(50), &config.lake),
+        GpuLayer::fbm(0, &distort),
+        GpuLayer::fbm(1, &distort),
+    ]
+}
+
+fn thresholds_array(t: &BiomeThresholds) -> [f64; THRESHOLD_COUNT] {
+    [
+        t.snow,
+        t.high_mountain,
+        t.medium_mountain,
+        t.mountain,
+        t.cliff_altitude,
+        t.medium_cliff_bias,
+        t.cliff_bias,
+        t.land,
+        t.sand_temperature,
+        t.dirt_temperature,
+        t.grass_temperature,
+        t.tree_vegetation,
+        t.tundra_temperature,
+        t.beach_altitude,
+        t.beach_bias,
+        t.beach_temperature,
+        t.shallow_water,
+        t.ice_temperature,
+        t.water,
+        t.medium_water,
+    ]
+}
+
+fn resize_ssbo<T>(ssbo: GLuint, data: &[T], binding: GLuint) {
+    unsafe {
+        gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
+        gl::BufferData(
+            gl::SHADER_STORAGE_BUFFER,
+            std::mem::size_of_val(data) as GLsizeiptr,
+            data.as_ptr() as *const GLvoid,
+            gl::DYNAMIC_DRAW,
+        );
+        gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, binding, ssbo);
+    }
+}
+
+// Evaluates the noise fields and biomes in a compute shader, writing colors and fields
+// straight into the world buffers; rivers are still traced on the CPU.
+pub struct GpuGenerator {
+    program: GLuint,
+    permutations_ssbo: GLuint,
+    cells_ssbo: GLuint,
+}
+
+impl GpuGenerator {
+    pub fn new() -> Result<GpuGenerator, String> {
+        let program = create_compute_program(GENERATE_COMPUTE_SRC)
+            .map_err(|e| format!("generate_compute.glsl: {}", e))?;
+
+        let mut buffers = [0; 2];
+        unsafe { gl::GenBuffers(2, buffers.as_mut_ptr()) };
+
+        Ok(GpuGenerator {
+            program,
+            permutations_ssbo: buffers[0],
+            cells_ssbo: buffers[1],
+        })
+    }
+
+    // Cells before rivers are added; `buffers` must have the size of the generated world.
+    pub fn generate_cells(
+        self: &Self,
+        seed: u64,
+        config: &WorldGenConfig,
+        buffers: &mut WorldBuffers,
+    ) -> Result<Vec<Vec<Cell>>, String> {
+        let (world_width, world_height) = buffers.world_size;
+        let layers = layers(seed, config);
+
+        let mut permutations: Vec<u32> = vec![];
+        let mut tables = [0; LAYER_COUNT];
+        for (i, layer) in layers.iter().enumerate() {
+            tables[i] = (permutations.len() / TABLE_SIZE) as i32;
+            for octave in 0..layer.octaves {
+                let table = PermutationTable::new(layer.seed.wrapping_add(octave as u32));
+                permutations.extend((0..TABLE_SIZE).map(|i| table.hash(&[i as isize]) as u32));
+            }
+        }
+        resize_ssbo(self.permutations_ssbo, &permutations, 3);
+
+        let mut cells = vec![EMPTY_CELL; (world_width * world_height) as usize];
+        resize_ssbo(self.cells_ssbo, &cells, 4);
+
+        let octaves = layers.each_ref().map(|layer| layer.octaves as i32);
+        let frequencies = layers.each_ref().map(|layer| layer.frequency);
+        let lacunarities = layers.each_ref().map(|layer| layer.lacunarity);
+        let persistences = layers.each_ref().map(|layer| layer.persistence);
+        let scale_factors = layers.each_ref().map(|layer| layer.scale_factor);
+        let thresholds = thresholds_array(&config.thresholds);
+        let location = |name: &str| uniform_location(self.program, name);
+
+        unsafe {
+            gl::UseProgram(self.program);
+            gl::Uniform2i(location("world_size"), world_width, world_height);
+            gl::Uniform1d(location("scale"), config.scale);
+            gl::Uniform1iv(location("layer_table"), LAYER_COUNT as i32, tables.as_ptr());
+            gl::Uniform1iv(
+                location("layer_octaves"),
+                LAYER_COUNT as i32,
+                octaves.as_ptr(),
+            );
+            gl::Uniform1dv(
+                location("layer_frequency"),
+                LAYER_COUNT as i32,
+                frequencies.as_ptr(),
+            );
+            gl::Uniform1dv(
+                location("layer_lacunarity"),
+                LAYER_COUNT as i32,
+                lacunarities.as_ptr(),
+            );
+            gl::Uniform1dv(
+                location("layer_persistence"),
+                LAYER_COUNT as i32,
+                persistences.as_ptr(),
+            );
+            gl::Uniform1dv(
+                location("layer_scale"),
+                LAYER_COUNT as i32,
+                scale_factors.as_ptr(),
+            );
+            gl::Uniform1d(location("turbulence_power"), config.vegetation_power);
+            gl::Uniform1d(location("cliff_attenuation"), config.cliff_attenuation);
+            gl::Uniform1f(location("cliff_exponent"), config.cliff_exponent as f32);
+            gl::Uniform1dv(
+                location("thresholds"),
+                THRESHOLD_COUNT as i32,
+                thresholds.as_ptr(),
+            );
+
+            gl::DispatchCompute(
+                (world_width as GLuint).div_ceil(WORK_GROUP_SIZE),
+                (world_height as GLuint).div_ceil(WORK_GROUP_SIZE),
+                1,
+            );
+            gl::MemoryBarrier(gl::SHADER_STORAGE_BARRIER_BIT | gl::BUFFER_UPDATE_BARRIER_BIT);
+
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.cells_ssbo);
+            gl::GetBufferSubData(
+                gl::SHADER_STORAGE_BUFFER,
+                0,
+                (cells.len() * std::mem::size_of::<GeneratedCell>()) as GLsizeiptr,
+                cells.as_mut_ptr() as *mut GLvoid,
+            );
+        }
+        buffers.read_back();
+
+        cells
+            .chunks(world_width as usize)
+            .map(|row| {
+                row.iter()
+                    .map(|cell| {
+                        let cell_type = u8::try_from(cell.cell_type)
+                            .ok()
+                            .and_then(CellType::from_u8)
+                            .ok_or(format!(
+                                "compute shader returned unknown cell type {}",
+                                cell.cell_type
+                            ))?;
+                        Ok(Cell {
+                            cell_type,
+                            altitude: cell.altitude,
+                            relative_altitude: cell.relative_altitude,
+                            temperature: cell.temperature,
+                            vegetation: cell.vegetation,
+                            beach_bias: cell.beach_bias,
+                            cliff_bias: cell.cliff_bias,
+                            lake_bias: cell.lake_bias,
+                        })
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // The world and the buffers have no rivers yet; `finish_world` traces them on the CPU.
+    pub fn generate(
+        self: &Self,
+        seed: u64,
+        config: &WorldGenConfig,
+        buffers: &mut WorldBuffers,
+    ) -> Result<World, String> {
+        let map = self.generate_cells(seed, config, buffers)?;
+
+        let (world_width, world_height) = buffers.world_size;
+        Ok(World {
+            map,
+            rivers: vec![],
+            layers: Layers::new(world_width, world_height),
+            config: *config,
+        })
+    }
+}
+
+impl Drop for GpuGenerator {
+    fn drop(self: &mut Self) {
+        unsafe {
+            gl::DeleteProgram(self.program);
+            gl::DeleteBuffers(1, &self.permutations_ssbo);
+            gl::DeleteBuffers(1, &self.cells_ssbo);
+        }
+    }
+}
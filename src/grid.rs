@@ -0,0 +1,283 @@
+use crate::{Cell, CellType, hex::hex_index};
+
+// Offsets of the six neighbours in doublewidth coordinates, in the order of `get_neighbors`.
+pub const HEX_NEIGHBORS: [(i32, i32); 6] = [(2, 0), (1, -1), (-1, -1), (-2, 0), (-1, 1), (1, 1)];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GridField {
+    Altitude,
+    RelativeAltitude,
+    Temperature,
+    Vegetation,
+    BeachBias,
+    CliffBias,
+    LakeBias,
+}
+
+impl GridField {
+    pub const ALL: [GridField; 7] = [
+        GridField::Altitude,
+        GridField::RelativeAltitude,
+        GridField::Temperature,
+        GridField::Vegetation,
+        GridField::BeachBias,
+        GridField::CliffBias,
+        GridField::LakeBias,
+    ];
+}
+
+// Cells stored field by field: one byte per cell type, and a single block holding every
+// noise field back to back, each a row-major run indexed `x + y * width`. Noise values are
+// kept as `f32`, which is what the renderer uploads anyway.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorldGrid {
+    width: usize,
+    height: usize,
+    cell_types: Vec<CellType>,
+    fields: Vec<f32>,
+}
+
+impl WorldGrid {
+    pub fn new(world_width: i32, world_height: i32) -> WorldGrid {
+        let len = world_width as usize * world_height as usize;
+
+        WorldGrid {
+            width: world_width as usize,
+            height: world_height as usize,
+            cell_types: vec![CellType::DeepWater; len],
+            fields: vec![0.0; len * GridField::ALL.len()],
+        }
+    }
+
+    pub fn from_map(map: &[Vec<Cell>]) -> WorldGrid {
+        let mut grid = WorldGrid::new(map[0].len() as i32, map.len() as i32);
+
+        for (i, cell) in map.iter().flatten().enumerate() {
+            grid.set(i, cell);
+        }
+
+        grid
+    }
+
+    pub fn to_map(self: &Self) -> Vec<Vec<Cell>> {
+        (0..self.height)
+            .map(|y| {
+                (0..self.width)
+                    .map(|x| self.cell(self.index(x, y)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn size(self: &Self) -> (i32, i32) {
+        (self.width as i32, self.height as i32)
+    }
+
+    pub fn len(self: &Self) -> usize {
+        self.cell_types.len()
+    }
+
+    pub fn is_empty(self: &Self) -> bool {
+        self.cell_types.is_empty()
+    }
+
+    // Bytes used by the cells.
+    pub fn memory(self: &Self) -> usize {
+        self.cell_types.len() * std::mem::size_of::<CellType>()
+            + self.fields.len() * std::mem::size_of::<f32>()
+    }
+
+    pub fn index(self: &Self, x: usize, y: usize) -> usize {
+        x + y * self.width
+    }
+
+    pub fn position(self: &Self, i: usize) -> (usize, usize) {
+        (i % self.width, i / self.width)
+    }
+
+    pub fn hex_index(self: &Self, hex: (i32, i32)) -> Option<usize> {
+        hex_index(self.width, self.height, hex)
+    }
+
+    pub fn cell_types(self: &Self) -> &[CellType] {
+        &self.cell_types
+    }
+
+    pub fn cell_types_mut(self: &mut Self) -> &mut [CellType] {
+        &mut self.cell_types
+    }
+
+    pub fn field(self: &Self, field: GridField) -> &[f32] {
+        let len = self.len();
+        &self.fields[field as usize * len..(field as usize + 1) * len]
+    }
+
+    pub fn field_mut(self: &mut Self, field: GridField) -> &mut [f32] {
+        let len = self.len();
+        &mut self.fields[field as usize * len..(field as usize + 1) * len]
+    }
+
+    pub fn cell_type_at(self: &Self, hex: (i32, i32)) -> Option<CellType> {
+        self.hex_index(hex).map(|i| self.cell_types[i])
+    }
+
+    pub fn field_at(self: &Self, field: GridField, hex: (i32, i32)) -> Option<f32> {
+        self.hex_index(hex).map(|i| self.field(field)[i])
+    }
+
+    pub fn altitude_at(self: &Self, hex: (i32, i32)) -> Option<f32> {
+        self.field_at(GridField::Altitude, hex)
+    }
+
+    pub fn cell_at(self: &Self, hex: (i32, i32)) -> Option<Cell> {
+        self.hex_index(hex).map(|i| self.cell(i))
+    }
+
+    // Returns `false` when the cell is outside the world.
+    pub fn set_at(self: &mut Self, hex: (i32, i32), cell: &Cell) -> bool {
+        let Some(i) = self.hex_index(hex) else {
+            return false;
+        };
+        self.set(i, cell);
+        true
+    }
+
+    // Doublewidth coordinates of the neighbours inside the world.
+    pub fn neighbors(self: &Self, hex: (i32, i32)) -> impl Iterator<Item = (i32, i32)> + '_ {
+        HEX_NEIGHBORS
+            .iter()
+            .map(move |offset| (hex.0 + offset.0, hex.1 + offset.1))
+            .filter(|neighbor| self.hex_index(*neighbor).is_some())
+    }
+
+    pub fn cell(self: &Self, i: usize) -> Cell {
+        let value = |field: GridField| self.fields[field as usize * self.len() + i] as f64;
+        Cell {
+            cell_type: self.cell_types[i],
+            altitude: value(GridField::Altitude),
+            relative_altitude: value(GridField::RelativeAltitude),
+            temperature: value(GridField::Temperature),
+            vegetation: value(GridField::Vegetation),
+            beach_bias: value(GridField::BeachBias),
+            cliff_bias: value(GridField::CliffBias),
+            lake_bias: value(GridField::LakeBias),
+        }
+    }
+
+    pub fn set(self: &mut Self, i: usize, cell: &Cell) {
+        let len = self.len();
+        self.cell_types[i] = cell.cell_type;
+        for (field, value) in [
+            (GridField::Altitude, cell.altitude),
+            (GridField::RelativeAltitude, cell.relative_altitude),
+            (GridField::Temperature, cell.temperature),
+            (GridField::Vegetation, cell.vegetation),
+            (GridField::BeachBias, cell.beach_bias),
+            (GridField::CliffBias, cell.cliff_bias),
+            (GridField::LakeBias, cell.lake_bias),
+        ] {
+            self.fields[field as usize * len + i] = value as f32;
+        }
+    }
+
+    // Positions of all cells in storage order.
+    pub fn positions(self: &Self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.len()).map(|i| self.position(i))
+    }
+
+    // Doublewidth coordinates of all cells in storage order.
+    pub fn hexes(self: &Self) -> impl Iterator<Item = (i32, i32)> + '_ {
+        self.positions()
+            .map(|(x, y)| ((x * 2 + y % 2) as i32, y as i32))
+    }
+
+    pub fn row_cell_types(self: &Self, y: usize) -> &[CellType] {
+        &self.cell_types[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn row(self: &Self, field: GridField, y: usize) -> &[f32] {
+        &self.field(field)[y * self.width..(y + 1) * self.width]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_world, get_neighbors};
+
+    #[test]
+    fn round_trips_a_map() {
+        let world = generate_world(3, 20, 12);
+        let grid = WorldGrid::from_map(&world.map);
+        let map = grid.to_map();
+
+        assert_eq!(grid.size(), (20, 12));
+        for (cell, original) in map.iter().flatten().zip(world.map.iter().flatten()) {
+            assert_eq!(cell.cell_type, original.cell_type);
+            assert_eq!(cell.altitude, original.altitude as f32 as f64);
+            assert_eq!(cell.lake_bias, original.lake_bias as f32 as f64);
+        }
+        assert_eq!(WorldGrid::from_map(&map), grid);
+    }
+
+    #[test]
+    fn looks_up_cells_by_hex() {
+        let world = generate_world(3, 20, 12);
+        let grid = WorldGrid::from_map(&world.map);
+
+        for (hex, (x, y)) in grid.hexes().zip(grid.positions()) {
+            let cell = &world.map[y][x];
+            assert_eq!(hex, ((x * 2 + y % 2) as i32, y as i32));
+            assert_eq!(grid.cell_type_at(hex), Some(cell.cell_type));
+            assert_eq!(grid.altitude_at(hex), Some(cell.altitude as f32));
+            assert_eq!(
+                grid.field_at(GridField::Temperature, hex),
+                Some(cell.temperature as f32)
+            );
+            assert_eq!(grid.row_cell_types(y)[x], cell.cell_type);
+            assert_eq!(
+                grid.row(GridField::Vegetation, y)[x],
+                cell.vegetation as f32
+            );
+        }
+    }
+
+    #[test]
+    fn ignores_hexes_outside_the_world() {
+        let mut grid = WorldGrid::new(4, 3);
+        let cell = grid.cell(0);
+
+        for hex in [(-1, 0), (0, -1), (8, 0), (0, 3)] {
+            assert_eq!(grid.hex_index(hex), None);
+            assert_eq!(grid.cell_at(hex), None);
+            assert!(!grid.set_at(hex, &cell));
+        }
+    }
+
+    #[test]
+    fn neighbors_match_the_map() {
+        let world = generate_world(3, 20, 12);
+        let grid = WorldGrid::from_map(&world.map);
+
+        for (x, y) in grid.positions() {
+            let hex = ((x * 2 + y % 2) as i32, y as i32);
+            let expected: Vec<(i32, i32)> = get_neighbors(&world.map, hex.0 as usize, y)
+                .into_iter()
+                .map(|(nx, ny)| (nx as i32, ny as i32))
+                .collect();
+            assert_eq!(grid.neighbors(hex).collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn fields_are_separate_runs() {
+        let mut grid = WorldGrid::new(4, 3);
+        grid.field_mut(GridField::Temperature).fill(1.0);
+        grid.cell_types_mut()[5] = CellType::Grass;
+
+        assert!(grid.field(GridField::Vegetation).iter().all(|v| *v == 0.0));
+        assert!(grid.field(GridField::Temperature).iter().all(|v| *v == 1.0));
+        assert_eq!(grid.cell_at((3, 1)).unwrap().cell_type, CellType::Grass);
+        assert_eq!(grid.memory(), 12 + 12 * 7 * 4);
+    }
+}
@@ -0,0 +1,102 @@
+pub const HEX_SIZE: f32 = 20.0;
+
+fn axial_to_cube(hex: (f32, f32)) -> (f32, f32, f32) {
+    let x = hex.0;
+    let y = hex.1;
+    let z = -x - y;
+    (x, y, z)
+}
+
+fn cube_round(frac: (f32, f32, f32)) -> (f32, f32, f32) {
+    let mut x = frac.0.round();
+    let mut y = frac.1.round();
+    let mut z = frac.2.round();
+
+    let q_diff = (x - frac.0).abs();
+    let r_diff = (y - frac.1).abs();
+    let s_diff = (z - frac.2).abs();
+
+    if q_diff > r_diff && q_diff > s_diff {
+        x = -y - z;
+    } else if r_diff > s_diff {
+        y = -x - z;
+    } else {
+        z = -x - y;
+    }
+
+    (x, y, z)
+}
+
+fn cube_to_axial(cube: (f32, f32, f32)) -> (f32, f32) {
+    (cube.0, cube.1)
+}
+
+fn axial_round(hex: (f32, f32)) -> (f32, f32) {
+    cube_to_axial(cube_round(axial_to_cube(hex)))
+}
+
+pub fn pixel_to_pointy_hex(point: (f32, f32), size: f32) -> (f32, f32) {
+    let x = point.0 / size;
+    let y = point.1 / size;
+
+    let q = (3.0_f32.sqrt() / 3.0) * x - (1.0 / 3.0) * y;
+    let r = (2.0 / 3.0) * y;
+
+    axial_round((q, r))
+}
+
+pub fn axial_to_doublewidth(hex: (f32, f32)) -> (f32, f32) {
+    let col = 2.0 * hex.0 + hex.1;
+    let row = hex.1;
+    (col, row)
+}
+
+pub fn pixel_to_doublewidth(pixel: (f32, f32), size: f32) -> (f32, f32) {
+    let axial_pos = pixel_to_pointy_hex(pixel, size);
+    axial_to_doublewidth(axial_pos)
+}
+
+pub fn doublewidth_to_pixel(hex: (f32, f32), size: f32) -> (f32, f32) {
+    let x = size * 3.0_f32.sqrt() / 2.0 * hex.0;
+    let y = size * 3.0 / 2.0 * hex.1;
+    (x, y)
+}
+
+pub fn cell_to_pixel(x: usize, y: usize, size: f32) -> (f32, f32) {
+    doublewidth_to_pixel(((x * 2 + y % 2) as f32, y as f32), size)
+}
+
+// Row-major index of the cell at a doublewidth hex coordinate, `None` outside the world.
+pub fn hex_index(width: usize, height: usize, hex: (i32, i32)) -> Option<usize> {
+    let (col, row) = hex;
+    if col < 0 || row < 0 || col >= width as i32 * 2 || row >= height as i32 {
+        return None;
+    }
+    Some(col as usize / 2 + row as usize * width)
+}
+
+pub fn hex_world_bounds(world_width: i32, world_height: i32, size: f32) -> (f32, f32, f32, f32) {
+    let half_width = size * 3.0_f32.sqrt() / 2.0;
+    let (max_x, max_y) = doublewidth_to_pixel(
+        ((world_width * 2 - 1) as f32, (world_height - 1) as f32),
+        size,
+    );
+    (-half_width, -size, max_x + half_width, max_y + size)
+}
+
+pub fn pixel_to_cell(
+    pixel: (f32, f32),
+    size: f32,
+    world_width: i32,
+    world_height: i32,
+) -> Option<(usize, usize)> {
+    let hex_pos = pixel_to_doublewidth(pixel, size);
+    let x = hex_pos.0 / 2.0;
+    let y = hex_pos.1;
+
+    if x < 0.0 || y < 0.0 || x >= world_width as f32 || y >= world_height as f32 {
+        return None;
+    }
+
+    Some((x as usize, y as usize))
+}
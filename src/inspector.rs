@@ -0,0 +1,69 @@
+use civilizations::{Camera2D, Cell, HEX_SIZE, get_world_size, raster};
+
+const CLICK_DISTANCE: f32 = 3.0;
+
+pub struct Inspector {
+    pub hovered: Option<(usize, usize)>,
+    pub selected: Option<(usize, usize)>,
+    press: Option<(f32, f32)>,
+}
+
+impl Inspector {
+    pub fn new() -> Inspector {
+        Inspector {
+            hovered: None,
+            selected: None,
+            press: None,
+        }
+    }
+
+    pub fn hover(self: &mut Self, camera: &Camera2D, mouse: (f32, f32), world_size: (i32, i32)) {
+        self.hovered = raster::pick_cell(
+            (mouse.0 as f64, mouse.1 as f64),
+            camera,
+            HEX_SIZE,
+            world_size.0,
+            world_size.1,
+        );
+    }
+
+    // Keeps the selection on the same cell when the map is moved by `(dx, dy)` cells.
+    pub fn shift(self: &mut Self, dx: i32, dy: i32, world_size: (i32, i32)) {
+        self.hovered = None;
+        self.selected = self.selected.and_then(|(x, y)| {
+            let (x, y) = (x as i32 - dx, y as i32 - dy);
+            (x >= 0 && y >= 0 && x < world_size.0 && y < world_size.1)
+                .then_some((x as usize, y as usize))
+        });
+    }
+
+    pub fn press(self: &mut Self, x: f32, y: f32) {
+        self.press = Some((x, y));
+    }
+
+    pub fn release(self: &mut Self, map: &[Vec<Cell>], camera: &Camera2D, x: f32, y: f32) {
+        let Some((press_x, press_y)) = self.press.take() else {
+            return;
+        };
+        if (x - press_x).abs() > CLICK_DISTANCE || (y - press_y).abs() > CLICK_DISTANCE {
+            return;
+        }
+
+        self.hover(camera, (x, y), get_world_size(map));
+        self.selected = self.hovered;
+    }
+
+    pub fn title(self: &Self, map: &[Vec<Cell>]) -> Option<String> {
+        let (x, y) = self.hovered?;
+        let cell = &map[y][x];
+        Some(format!(
+            "({}, {}) {:?} - altitude {:.3}, relative {:.3}, weight {}",
+            x,
+            y,
+            cell.cell_type,
+            cell.altitude,
+            cell.relative_altitude,
+            cell.cell_type.get_weight()
+        ))
+    }
+}
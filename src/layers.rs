@@ -0,0 +1,367 @@
+use crate::hex::hex_index;
+
+// Owner ids start at 1, cells nobody owns hold `NO_OWNER`.
+pub const NO_OWNER: u32 = 0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum LayerKind {
+    Scalar,
+    Category,
+    Owner,
+}
+
+impl LayerKind {
+    pub const ALL: [LayerKind; 3] = [LayerKind::Scalar, LayerKind::Category, LayerKind::Owner];
+
+    pub fn from_u8(value: u8) -> Option<LayerKind> {
+        LayerKind::ALL.get(value as usize).copied()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LayerData {
+    Scalar(Vec<f32>),
+    Category(Vec<u8>),
+    Owner(Vec<u32>),
+}
+
+impl LayerData {
+    pub fn new(kind: LayerKind, len: usize) -> LayerData {
+        match kind {
+            LayerKind::Scalar => LayerData::Scalar(vec![0.0; len]),
+            LayerKind::Category => LayerData::Category(vec![0; len]),
+            LayerKind::Owner => LayerData::Owner(vec![NO_OWNER; len]),
+        }
+    }
+
+    pub fn kind(self: &Self) -> LayerKind {
+        match self {
+            LayerData::Scalar(_) => LayerKind::Scalar,
+            LayerData::Category(_) => LayerKind::Category,
+            LayerData::Owner(_) => LayerKind::Owner,
+        }
+    }
+
+    pub fn len(self: &Self) -> usize {
+        match self {
+            LayerData::Scalar(values) => values.len(),
+            LayerData::Category(values) => values.len(),
+            LayerData::Owner(values) => values.len(),
+        }
+    }
+
+    pub fn is_empty(self: &Self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn memory(self: &Self) -> usize {
+        match self {
+            LayerData::Scalar(values) => values.len() * std::mem::size_of::<f32>(),
+            LayerData::Category(values) => values.len(),
+            LayerData::Owner(values) => values.len() * std::mem::size_of::<u32>(),
+        }
+    }
+
+    // Any kind of value as a float, which is how overlays and exports see them.
+    pub fn value(self: &Self, i: usize) -> f32 {
+        match self {
+            LayerData::Scalar(values) => values[i],
+            LayerData::Category(values) => values[i] as f32,
+            LayerData::Owner(values) => values[i] as f32,
+        }
+    }
+
+    pub fn values(self: &Self) -> Vec<f32> {
+        (0..self.len()).map(|i| self.value(i)).collect()
+    }
+
+    // Lowest and highest value, `(0.0, 0.0)` for an empty layer.
+    pub fn range(self: &Self) -> (f32, f32) {
+        if self.is_empty() {
+            return (0.0, 0.0);
+        }
+        (0..self.len())
+            .map(|i| self.value(i))
+            .fold((f32::MAX, f32::MIN), |(low, high), value| {
+                (low.min(value), high.max(value))
+            })
+    }
+}
+
+pub struct Layer {
+    pub name: String,
+    pub data: LayerData,
+}
+
+// Named per cell values that any subsystem can attach to a world, stored row-major and
+// looked up by doublewidth hex coordinate.
+pub struct Layers {
+    width: usize,
+    height: usize,
+    layers: Vec<Layer>,
+}
+
+impl Layers {
+    pub fn new(world_width: i32, world_height: i32) -> Layers {
+        Layers {
+            width: world_width as usize,
+            height: world_height as usize,
+            layers: vec![],
+        }
+    }
+
+    pub fn size(self: &Self) -> (i32, i32) {
+        (self.width as i32, self.height as i32)
+    }
+
+    pub fn len(self: &Self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(self: &Self) -> bool {
+        self.layers.is_empty()
+    }
+
+    pub fn iter(self: &Self) -> impl Iterator<Item = &Layer> {
+        self.layers.iter()
+    }
+
+    pub fn memory(self: &Self) -> usize {
+        self.layers.iter().map(|layer| layer.data.memory()).sum()
+    }
+
+    // Adds an empty layer, or returns the existing one when it has the same kind.
+    pub fn register(self: &mut Self, name: &str, kind: LayerKind) -> Result<usize, String> {
+        if let Some(i) = self.position(name) {
+            let existing = self.layers[i].data.kind();
+            if existing != kind {
+                return Err(format!(
+                    "layer {} is registered as {:?}, not {:?}",
+                    name, existing, kind
+                ));
+            }
+            return Ok(i);
+        }
+
+        self.layers.push(Layer {
+            name: String::from(name),
+            data: LayerData::new(kind, self.width * self.height),
+        });
+        Ok(self.layers.len() - 1)
+    }
+
+    // Adds a filled layer, replacing any layer with the same name.
+    pub fn insert(self: &mut Self, name: &str, data: LayerData) -> Result<usize, String> {
+        if data.len() != self.width * self.height {
+            return Err(format!(
+                "layer {} has {} values for {} cells",
+                name,
+                data.len(),
+                self.width * self.height
+            ));
+        }
+
+        let layer = Layer {
+            name: String::from(name),
+            data,
+        };
+        match self.position(name) {
+            Some(i) => {
+                self.layers[i] = layer;
+                Ok(i)
+            }
+            None => {
+                self.layers.push(layer);
+                Ok(self.layers.len() - 1)
+            }
+        }
+    }
+
+    pub fn remove(self: &mut Self, name: &str) -> Option<Layer> {
+        self.position(name).map(|i| self.layers.remove(i))
+    }
+
+    pub fn position(self: &Self, name: &str) -> Option<usize> {
+        self.layers.iter().position(|layer| layer.name == name)
+    }
+
+    pub fn get(self: &Self, name: &str) -> Option<&Layer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    // Private so a layer's data can't be swapped for one of another length.
+    fn get_mut(self: &mut Self, name: &str) -> Option<&mut Layer> {
+        self.layers.iter_mut().find(|layer| layer.name == name)
+    }
+
+    pub fn at(self: &Self, i: usize) -> Option<&Layer> {
+        self.layers.get(i)
+    }
+
+    pub fn hex_index(self: &Self, hex: (i32, i32)) -> Option<usize> {
+        hex_index(self.width, self.height, hex)
+    }
+
+    pub fn scalars(self: &Self, name: &str) -> Option<&[f32]> {
+        match &self.get(name)?.data {
+            LayerData::Scalar(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn scalars_mut(self: &mut Self, name: &str) -> Option<&mut [f32]> {
+        match &mut self.get_mut(name)?.data {
+            LayerData::Scalar(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn categories(self: &Self, name: &str) -> Option<&[u8]> {
+        match &self.get(name)?.data {
+            LayerData::Category(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn categories_mut(self: &mut Self, name: &str) -> Option<&mut [u8]> {
+        match &mut self.get_mut(name)?.data {
+            LayerData::Category(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn owners(self: &Self, name: &str) -> Option<&[u32]> {
+        match &self.get(name)?.data {
+            LayerData::Owner(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn owners_mut(self: &mut Self, name: &str) -> Option<&mut [u32]> {
+        match &mut self.get_mut(name)?.data {
+            LayerData::Owner(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    // `None` when the layer is missing, has another kind or the cell is outside the world.
+    pub fn scalar_at(self: &Self, name: &str, hex: (i32, i32)) -> Option<f32> {
+        let i = self.hex_index(hex)?;
+        self.scalars(name).map(|values| values[i])
+    }
+
+    pub fn category_at(self: &Self, name: &str, hex: (i32, i32)) -> Option<u8> {
+        let i = self.hex_index(hex)?;
+        self.categories(name).map(|values| values[i])
+    }
+
+    pub fn owner_at(self: &Self, name: &str, hex: (i32, i32)) -> Option<u32> {
+        let i = self.hex_index(hex)?;
+        self.owners(name).map(|values| values[i])
+    }
+
+    pub fn set_scalar(self: &mut Self, name: &str, hex: (i32, i32), value: f32) -> bool {
+        let Some(i) = self.hex_index(hex) else {
+            return false;
+        };
+        self.scalars_mut(name)
+            .map(|values| values[i] = value)
+            .is_some()
+    }
+
+    pub fn set_category(self: &mut Self, name: &str, hex: (i32, i32), value: u8) -> bool {
+        let Some(i) = self.hex_index(hex) else {
+            return false;
+        };
+        self.categories_mut(name)
+            .map(|values| values[i] = value)
+            .is_some()
+    }
+
+    pub fn set_owner(self: &mut Self, name: &str, hex: (i32, i32), value: u32) -> bool {
+        let Some(i) = self.hex_index(hex) else {
+            return false;
+        };
+        self.owners_mut(name)
+            .map(|values| values[i] = value)
+            .is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registering_again_returns_the_same_layer() {
+        let mut layers = Layers::new(4, 3);
+        let i = layers.register("height", LayerKind::Scalar).unwrap();
+        assert_eq!(layers.register("height", LayerKind::Scalar), Ok(i));
+        assert_eq!(layers.len(), 1);
+        assert_eq!(layers.at(i).unwrap().data.len(), 12);
+    }
+
+    #[test]
+    fn rejects_a_kind_mismatch() {
+        let mut layers = Layers::new(4, 3);
+        layers.register("owner", LayerKind::Owner).unwrap();
+        assert!(layers.register("owner", LayerKind::Scalar).is_err());
+
+        layers.set_owner("owner", (2, 1), 7);
+        assert_eq!(layers.owner_at("owner", (2, 1)), Some(7));
+        assert_eq!(layers.scalar_at("owner", (2, 1)), None);
+        assert_eq!(layers.category_at("owner", (2, 1)), None);
+        assert!(!layers.set_scalar("owner", (2, 1), 1.0));
+        assert!(layers.scalars("owner").is_none());
+    }
+
+    #[test]
+    fn rejects_data_of_the_wrong_length() {
+        let mut layers = Layers::new(4, 3);
+        assert!(
+            layers
+                .insert("short", LayerData::Scalar(vec![0.0; 11]))
+                .is_err()
+        );
+        assert!(
+            layers
+                .insert("long", LayerData::Category(vec![0; 13]))
+                .is_err()
+        );
+        assert!(layers.is_empty());
+
+        assert_eq!(layers.insert("fine", LayerData::Owner(vec![1; 12])), Ok(0));
+        // Inserting under the same name replaces the layer.
+        assert_eq!(
+            layers.insert("fine", LayerData::Scalar(vec![0.5; 12])),
+            Ok(0)
+        );
+        assert_eq!(layers.scalar_at("fine", (0, 0)), Some(0.5));
+    }
+
+    #[test]
+    fn looks_up_cells_by_hex() {
+        let mut layers = Layers::new(4, 3);
+        layers.register("type", LayerKind::Category).unwrap();
+
+        // Odd rows are shifted by one doublewidth column.
+        assert!(layers.set_category("type", (3, 1), 5));
+        assert_eq!(layers.categories("type").unwrap()[5], 5);
+        assert_eq!(layers.category_at("type", (3, 1)), Some(5));
+        assert_eq!(layers.category_at("type", (7, 2)), Some(0));
+    }
+
+    #[test]
+    fn ignores_hexes_outside_the_world() {
+        let mut layers = Layers::new(4, 3);
+        layers.register("height", LayerKind::Scalar).unwrap();
+
+        for hex in [(-1, 0), (0, -1), (8, 0), (0, 3)] {
+            assert_eq!(layers.hex_index(hex), None);
+            assert_eq!(layers.scalar_at("height", hex), None);
+            assert!(!layers.set_scalar("height", hex, 1.0));
+        }
+        assert_eq!(layers.scalar_at("missing", (0, 0)), None);
+    }
+}
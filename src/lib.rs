@@ -0,0 +1,30 @@
+//! Hex world generation, export and software rendering shared by the viewer and other tools.
+
+pub mod camera;
+pub mod chunks;
+pub mod config;
+pub mod dirty;
+pub mod export;
+pub mod geojson;
+pub mod grid;
+pub mod hex;
+pub mod layers;
+pub mod lighting;
+pub mod lod;
+pub mod raster;
+pub mod save;
+pub mod seasons;
+pub mod world;
+
+pub use camera::Camera2D;
+pub use config::{Climate, WorldGenConfig};
+pub use dirty::DirtyRegions;
+pub use grid::{GridField, WorldGrid};
+pub use hex::HEX_SIZE;
+pub use layers::{Layer, LayerData, LayerKind, Layers};
+pub use world::{
+    Cell, CellType, EdgeMode, Overlay, WORLD_HEIGTH, WORLD_WIDTH, World, generate_river,
+    generate_seed, generate_world, generate_world_colors, generate_world_fields,
+    generate_world_sequential, generate_world_with_config, get_neighbors, get_world_size,
+    reclassify, river_layers, update_world_colors, update_world_fields,
+};
@@ -0,0 +1,70 @@
+use std::f64::consts::PI;
+
+const SUN_STEP: f64 = PI / 12.0;
+const MIN_SUN_ELEVATION: f64 = PI / 36.0;
+const MAX_SUN_ELEVATION: f64 = PI / 2.0;
+const AMBIENT_STEP: f64 = 0.05;
+
+pub struct Lighting {
+    pub hillshade: bool,
+    pub contours: bool,
+    pub sun_azimuth: f64,
+    pub sun_elevation: f64,
+    pub ambient: f64,
+    pub relief: f64,
+    pub sea_level: f64,
+    pub contour_interval: f64,
+}
+
+impl Default for Lighting {
+    fn default() -> Lighting {
+        Lighting::new()
+    }
+}
+
+impl Lighting {
+    pub fn new() -> Lighting {
+        Lighting {
+            hillshade: false,
+            contours: false,
+            sun_azimuth: PI * 7.0 / 4.0,
+            sun_elevation: PI / 4.0,
+            ambient: 0.35,
+            relief: 8000.0,
+            sea_level: 0.6,
+            contour_interval: 0.025,
+        }
+    }
+
+    // Azimuth is measured clockwise from the top of the world, y points down.
+    pub fn sun_direction(self: &Self) -> (f64, f64, f64) {
+        let (sin_elevation, cos_elevation) = self.sun_elevation.sin_cos();
+        (
+            self.sun_azimuth.sin() * cos_elevation,
+            -self.sun_azimuth.cos() * cos_elevation,
+            sin_elevation,
+        )
+    }
+
+    pub fn rotate_sun(self: &mut Self, steps: f64) {
+        self.sun_azimuth = (self.sun_azimuth + steps * SUN_STEP).rem_euclid(2.0 * PI);
+    }
+
+    pub fn raise_sun(self: &mut Self, steps: f64) {
+        self.sun_elevation =
+            (self.sun_elevation + steps * SUN_STEP).clamp(MIN_SUN_ELEVATION, MAX_SUN_ELEVATION);
+    }
+
+    pub fn change_ambient(self: &mut Self, steps: f64) {
+        self.ambient = (self.ambient + steps * AMBIENT_STEP).clamp(0.0, 1.0);
+    }
+
+    pub fn describe(self: &Self) -> String {
+        format!(
+            "sun {:.0}° / {:.0}°, ambient {:.2}",
+            self.sun_azimuth.to_degrees(),
+            self.sun_elevation.to_degrees(),
+            self.ambient
+        )
+    }
+}
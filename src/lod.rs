@@ -0,0 +1,109 @@
+use crate::dirty::DirtyRegions;
+
+pub const MAX_LOD_LEVELS: usize = 16;
+
+pub struct ColorLevel {
+    pub width: i32,
+    pub height: i32,
+    pub colors: Vec<f32>,
+}
+
+fn downsample_cell(
+    colors: &[f32],
+    level_width: i32,
+    level_height: i32,
+    x: i32,
+    y: i32,
+) -> [f32; 4] {
+    let mut sum = [0.0; 4];
+    let mut count = 0.0;
+    for sy in y * 2..(y * 2 + 2).min(level_height) {
+        for sx in x * 2..(x * 2 + 2).min(level_width) {
+            let i = ((sx + sy * level_width) * 4) as usize;
+            for (total, value) in sum.iter_mut().zip(&colors[i..i + 4]) {
+                *total += value;
+            }
+            count += 1.0;
+        }
+    }
+    sum.map(|value| value / count)
+}
+
+fn downsample(colors: &[f32], level_width: i32, level_height: i32) -> ColorLevel {
+    let width = (level_width as u32).div_ceil(2) as i32;
+    let height = (level_height as u32).div_ceil(2) as i32;
+    let mut downsampled: Vec<f32> = Vec::with_capacity((width * height * 4) as usize);
+
+    for y in 0..height {
+        for x in 0..width {
+            downsampled.extend_from_slice(&downsample_cell(
+                colors,
+                level_width,
+                level_height,
+                x,
+                y,
+            ));
+        }
+    }
+
+    ColorLevel {
+        width,
+        height,
+        colors: downsampled,
+    }
+}
+
+// Returns the downsampled levels only, level 0 is the full resolution `colors`.
+pub fn generate_color_pyramid(
+    colors: &[f32],
+    world_width: i32,
+    world_height: i32,
+) -> Vec<ColorLevel> {
+    let mut levels: Vec<ColorLevel> = vec![downsample(colors, world_width, world_height)];
+
+    while levels.len() + 1 < MAX_LOD_LEVELS {
+        let last = levels.last().unwrap();
+        if last.width == 1 && last.height == 1 {
+            break;
+        }
+        levels.push(downsample(&last.colors, last.width, last.height));
+    }
+
+    levels
+}
+
+// Recomputes the cells of every level that cover `dirty` cells of the full resolution `colors`.
+pub fn update_color_pyramid(
+    levels: &mut [ColorLevel],
+    colors: &[f32],
+    world_width: i32,
+    world_height: i32,
+    dirty: &DirtyRegions,
+) {
+    let mut dirty = dirty.downsample();
+
+    for i in 0..levels.len() {
+        let (previous, current) = levels.split_at_mut(i);
+        let (source, source_width, source_height) = match previous.last() {
+            Some(level) => (&level.colors[..], level.width, level.height),
+            None => (colors, world_width, world_height),
+        };
+        let level = &mut current[0];
+
+        for (x, y) in dirty.cells() {
+            let cell = downsample_cell(source, source_width, source_height, x as i32, y as i32);
+            let j = (x + y * level.width as usize) * 4;
+            level.colors[j..j + 4].copy_from_slice(&cell);
+        }
+
+        dirty = dirty.downsample();
+    }
+}
+
+pub fn pack_color(color: &[f32]) -> u32 {
+    let mut packed = 0;
+    for (c, value) in color.iter().take(4).enumerate() {
+        packed |= (((value / 255.0).clamp(0.0, 1.0) * 255.0).round() as u32) << (c * 8);
+    }
+    packed
+}
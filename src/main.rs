@@ -1,725 +1,813 @@
-use gl::types::{GLchar, GLsizeiptr, GLuint, GLvoid};
-use noise::{
-    Abs, Fbm, MultiFractal, NoiseFn, Perlin, RidgedMulti, ScalePoint, Turbulence, Vector4,
-    utils::{self, PlaneMapBuilder},
+mod cli;
+mod display;
+mod font;
+mod frame_timer;
+mod generator;
+mod gpu_generator;
+mod inspector;
+mod minimap;
+mod navigation;
+mod panels;
+mod renderer;
+mod shader_watcher;
+mod status;
+mod streaming;
+mod ui;
+mod world_buffers;
+
+use civilizations::{
+    Camera2D, Cell, CellType, Climate, DirtyRegions, EdgeMode, HEX_SIZE, Overlay, World,
+    WorldGenConfig, generate_world_colors, generate_world_fields, get_world_size,
+    lighting::Lighting,
+    raster::{self, RasterView, RasterWorld},
+    reclassify, seasons,
+    world::WorldNoise,
 };
-use rand::{Rng, seq::SliceRandom, thread_rng};
+use clap::Parser;
+use display::Display;
+use frame_timer::FrameTimer;
+use generator::Generator;
+use gpu_generator::GpuGenerator;
+use inspector::Inspector;
+use minimap::Minimap;
+use navigation::Navigation;
+use renderer::{Renderer, ShaderSources};
 use sdl3::{
+    EventPump, Sdl,
     event::Event,
-    keyboard::Keycode,
+    keyboard::{Keycode, Mod, Scancode},
     mouse::MouseButton,
-    video::{GLContext, GLProfile},
-};
-use std::{
-    ffi::CString,
-    fs, mem, ptr,
-    time::{Instant, SystemTime},
-    vec,
+    video::{GLContext, GLProfile, VideoSubsystem, Window},
 };
+use shader_watcher::ShaderWatcher;
+use status::StatusMessage;
+use std::{path::PathBuf, time::Instant};
+use streaming::Streaming;
+use ui::Ui;
+use world_buffers::WorldBuffers;
 
 const WINDOW_WIDTH: u32 = 1650;
 const WINDOW_HEIGHT: u32 = 1080;
 
-const WORLD_WIDTH: i32 = 1000;
-const WORLD_HEIGTH: i32 = 1000;
-
-struct Camera2D {
-    offset: (f64, f64),
-    position: (f64, f64),
-    rotation: f64,
-    zoom: f64,
-}
-
-impl Camera2D {
-    fn get_screen_to_world(self: &Self, screen_x: f64, screen_y: f64) -> (f64, f64) {
-        let mut world_x = screen_x;
-        let mut world_y = screen_y;
-        world_x -= self.offset.0;
-        world_y -= self.offset.1;
-        world_x /= self.zoom;
-        world_y /= self.zoom;
-        world_x -= -self.offset.0;
-        world_y -= -self.offset.1;
-
-        (world_x, world_y)
-    }
-}
-
-enum CellType {
-    Grass,
-    Dirt,
-    Tree,
-    River,
-    Water,
-    MediumWater,
-    DeepWater,
-    Sand,
-    Snow,
-    Mountain,
-    MediumMountain,
-    HighMountain,
-    Tundra,
-    ShallowWater,
-    Ice,
-    Cliff,
-    MediumCliff,
-    Lake,
-}
-
-fn increase_color_by_height(color: (f64, f64, f64), height: f64) -> (f64, f64, f64) {
-    (
-        (color.0 / (1.0 - height * 1.4)).max(0.0).min(255.0),
-        (color.1 / (1.0 - height * 1.4)).max(0.0).min(255.0),
-        (color.2 / (1.0 - height * 1.4)).max(0.0).min(255.0),
-    )
-}
-
-fn increase_color_by_height_water(color: (f64, f64, f64), height: f64) -> (f64, f64, f64) {
-    (
-        (color.0 / (1.0 - height * 7.0)).max(0.0).min(255.0),
-        (color.1 / (1.0 - height * 7.0)).max(0.0).min(255.0),
-        (color.2 / (1.0 - height * 7.0)).max(0.0).min(255.0),
-    )
-}
+const ROTATION_STEP: f64 = std::f64::consts::PI / 36.0;
+
+// Share of cells whose type, and of rendered pixels whose color, may differ between the
+// CPU and GPU generators, from single precision `pow` and rounding.
+const GPU_CHECK_MAX_TYPE_MISMATCH: f64 = 0.0001;
+const GPU_CHECK_MAX_PIXEL_MISMATCH: f64 = 0.001;
+
+const BOOKMARK_KEYS: [Keycode; 9] = [
+    Keycode::F1,
+    Keycode::F2,
+    Keycode::F3,
+    Keycode::F4,
+    Keycode::F5,
+    Keycode::F6,
+    Keycode::F7,
+    Keycode::F8,
+    Keycode::F9,
+];
+
+fn handle_input(
+    events: &mut EventPump,
+    map: &Vec<Vec<Cell>>,
+    camera: &mut Camera2D,
+    navigation: &mut Navigation,
+    inspector: &mut Inspector,
+    display: &mut Display,
+    ui: &mut Ui,
+) -> bool {
+    let mouse_state = events.mouse_state();
 
-fn decrease_color_by_height(color: (f64, f64, f64), height: f64) -> (f64, f64, f64) {
-    (
-        (color.0 * (1.0 - height.cbrt())).max(0.0).min(255.0),
-        (color.1 * (1.0 - height.cbrt())).max(0.0).min(255.0),
-        (color.2 * (1.0 - height.cbrt())).max(0.0).min(255.0),
-    )
-}
+    for event in events.poll_iter() {
+        if ui.handle_event(&event) {
+            continue;
+        }
 
-impl CellType {
-    fn get_weight(self: &Self) -> i32 {
-        match self {
-            CellType::Grass => 1,
-            CellType::Water => 5,
-            CellType::Sand => 2,
-            CellType::Snow => 2,
-            CellType::Mountain => 100,
-            CellType::DeepWater => 20,
-            CellType::MediumWater => 10,
-            CellType::River => 6,
-            CellType::Tundra => 2,
-            CellType::MediumMountain => 200,
-            CellType::HighMountain => 300,
-            CellType::Dirt => 1,
-            CellType::ShallowWater => 2,
-            CellType::Tree => 5,
-            CellType::Ice => 3,
-            CellType::Cliff => 5,
-            CellType::MediumCliff => 5,
-            CellType::Lake => 5,
+        if navigation.goto_input.is_some() {
+            match event {
+                Event::TextInput { text, .. } => {
+                    let input = navigation.goto_input.as_mut().unwrap();
+                    input.extend(text.chars().filter(|c| c.is_ascii_digit() || *c == ','));
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Backspace),
+                    ..
+                } => {
+                    navigation.goto_input.as_mut().unwrap().pop();
+                }
+                Event::KeyDown {
+                    keycode: Some(Keycode::Return),
+                    ..
+                } => navigation.submit_goto(camera),
+                Event::KeyDown {
+                    keycode: Some(Keycode::Escape),
+                    ..
+                } => navigation.goto_input = None,
+                Event::Quit { .. } => return false,
+                _ => {}
+            }
+            continue;
         }
-    }
-}
 
-struct Cell {
-    cell_type: CellType,
-    altitude: f64,
-    relative_altitude: f64,
-}
+        match event {
+            Event::Quit { .. }
+            | Event::KeyDown {
+                keycode: Some(Keycode::Escape),
+                ..
+            } => return false,
+            Event::KeyDown {
+                keycode: Some(keycode @ (Keycode::Q | Keycode::E)),
+                ..
+            } => {
+                let direction = if keycode == Keycode::Q { -1.0 } else { 1.0 };
 
-impl Cell {
-    fn get_color(self: &Self) -> (f64, f64, f64) {
-        match self.cell_type {
-            CellType::Grass => {
-                increase_color_by_height((125.0, 205.0, 127.0), self.relative_altitude)
-            }
-            CellType::ShallowWater => {
-                increase_color_by_height_water((40.0, 100.0, 160.0), self.relative_altitude)
-            }
-            CellType::Water => {
-                increase_color_by_height_water((15.0, 15.0, 160.0), self.relative_altitude)
-            }
-            CellType::MediumWater => {
-                increase_color_by_height_water((22.0, 30.0, 64.0), self.relative_altitude)
-            }
-            CellType::DeepWater => {
-                decrease_color_by_height((30.0, 50.0, 100.0), self.relative_altitude)
-            }
-            CellType::Sand => {
-                increase_color_by_height((230.0, 210.0, 100.0), self.relative_altitude)
-            }
-            CellType::Snow => {
-                increase_color_by_height((230.0, 230.0, 230.0), self.relative_altitude)
-            }
-            CellType::River => {
-                increase_color_by_height((50.0, 100.0, 150.0), self.relative_altitude)
-            }
-            CellType::Tundra => {
-                increase_color_by_height((20.0, 100.0, 20.0), self.relative_altitude)
-            }
-            CellType::Mountain => {
-                decrease_color_by_height((100.0, 100.0, 100.0), self.relative_altitude)
-            }
-            CellType::MediumMountain => {
-                decrease_color_by_height((80.0, 80.0, 80.0), self.relative_altitude)
-            }
-            CellType::HighMountain => {
-                decrease_color_by_height((60.0, 60.0, 60.0), self.relative_altitude)
+                navigation.stop(camera);
+                camera.rotate_around(
+                    mouse_state.x() as f64,
+                    mouse_state.y() as f64,
+                    direction * ROTATION_STEP,
+                );
             }
-            CellType::Dirt => {
-                increase_color_by_height((196.0, 210.0, 130.0), self.relative_altitude)
+            Event::KeyDown {
+                keycode: Some(Keycode::Equals | Keycode::Plus | Keycode::KpPlus),
+                ..
+            } => navigation.zoom_in(camera),
+            Event::KeyDown {
+                keycode: Some(Keycode::Minus | Keycode::KpMinus),
+                ..
+            } => navigation.zoom_out(camera),
+            Event::KeyDown {
+                keycode: Some(Keycode::Home),
+                ..
+            } => navigation.fit_world(camera),
+            Event::KeyDown {
+                keycode: Some(Keycode::Tab),
+                keymod,
+                ..
+            } => {
+                display.overlay = if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+                    display.overlay.previous()
+                } else {
+                    display.overlay.next()
+                };
             }
-            CellType::Tree => increase_color_by_height((50.0, 150.0, 50.0), self.relative_altitude),
-            CellType::Ice => {
-                increase_color_by_height_water((150.0, 150.0, 200.0), self.relative_altitude)
+            Event::KeyDown {
+                keycode: Some(Keycode::H),
+                ..
+            } => display.grid = !display.grid,
+            Event::KeyDown {
+                keycode: Some(Keycode::T),
+                ..
+            } => display.timing = !display.timing,
+            Event::KeyDown {
+                keycode: Some(Keycode::U),
+                ..
+            } => display.panels = !display.panels,
+            Event::KeyDown {
+                keycode: Some(Keycode::B),
+                ..
+            } => display.edge_mode = display.edge_mode.next(),
+            Event::KeyDown {
+                keycode: Some(Keycode::L),
+                ..
+            } => display.lighting.hillshade = !display.lighting.hillshade,
+            Event::KeyDown {
+                keycode: Some(Keycode::K),
+                ..
+            } => display.lighting.contours = !display.lighting.contours,
+            Event::KeyDown {
+                keycode: Some(keycode @ (Keycode::LeftBracket | Keycode::RightBracket)),
+                keymod,
+                ..
+            } => {
+                let direction = if keycode == Keycode::LeftBracket {
+                    -1.0
+                } else {
+                    1.0
+                };
+                if keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD) {
+                    display.lighting.raise_sun(direction);
+                } else {
+                    display.lighting.rotate_sun(direction);
+                }
             }
-            CellType::Cliff => {
-                decrease_color_by_height((150.0, 150.0, 130.0), self.relative_altitude)
+            Event::KeyDown {
+                keycode: Some(keycode @ (Keycode::Comma | Keycode::Period)),
+                ..
+            } => display
+                .lighting
+                .change_ambient(if keycode == Keycode::Comma { -1.0 } else { 1.0 }),
+            Event::KeyDown {
+                keycode: Some(Keycode::G),
+                ..
+            } => navigation.goto_input = Some(String::new()),
+            Event::KeyDown {
+                keycode: Some(keycode),
+                keymod,
+                repeat: false,
+                ..
+            } if BOOKMARK_KEYS.contains(&keycode) => {
+                let slot = BOOKMARK_KEYS
+                    .iter()
+                    .position(|key| *key == keycode)
+                    .unwrap();
+                if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) {
+                    navigation.save_bookmark(camera, slot);
+                } else {
+                    navigation.recall_bookmark(camera, slot);
+                }
             }
-            CellType::MediumCliff => {
-                decrease_color_by_height((130.0, 130.0, 110.0), self.relative_altitude)
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => inspector.press(x, y),
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => inspector.release(map, camera, x, y),
+            Event::MouseWheel {
+                y,
+                mouse_x,
+                mouse_y,
+                ..
+            } => navigation.zoom_by(mouse_x as f64, mouse_y as f64, y as f64),
+            Event::MouseMotion {
+                xrel,
+                yrel,
+                mousestate,
+                ..
+            } if mousestate.is_mouse_button_pressed(MouseButton::Left) => {
+                navigation.stop(camera);
+                camera.pan(xrel as f64, yrel as f64);
             }
-            CellType::Lake => {
-                increase_color_by_height((40.0, 100.0, 160.0), self.relative_altitude)
+            Event::MouseMotion {
+                x,
+                y,
+                xrel,
+                mousestate,
+                ..
+            } if mousestate.is_mouse_button_pressed(MouseButton::Right) => {
+                navigation.stop(camera);
+                camera.rotate_around(x as f64, y as f64, xrel as f64 * 0.005);
             }
+            _ => {}
         }
     }
-}
 
-fn get_neighbors(map: &Vec<Vec<Cell>>, x: usize, y: usize) -> Vec<(usize, usize)> {
-    let mut neighbors: Vec<(usize, usize)> = vec![];
+    true
+}
 
-    let h = map.len() as i32;
-    let w = map[0].len() as i32 * 2;
+fn get_pan_direction(events: &EventPump) -> (f64, f64) {
+    let keyboard = events.keyboard_state();
+    let pressed = |keys: [Scancode; 2]| keys.iter().any(|key| keyboard.is_scancode_pressed(*key));
 
-    if x as i32 + 2 < w {
-        neighbors.push((x + 2, y));
-    }
-    if x as i32 + 1 < w && y as i32 - 1 >= 0 {
-        neighbors.push((x + 1, y - 1));
+    let mut direction = (0.0, 0.0);
+    if pressed([Scancode::A, Scancode::Left]) {
+        direction.0 -= 1.0;
     }
-    if x as i32 - 1 >= 0 && y as i32 - 1 >= 0 {
-        neighbors.push((x - 1, y - 1));
+    if pressed([Scancode::D, Scancode::Right]) {
+        direction.0 += 1.0;
     }
-    if x as i32 - 2 >= 0 {
-        neighbors.push((x - 2, y));
+    if pressed([Scancode::W, Scancode::Up]) {
+        direction.1 -= 1.0;
     }
-    if x as i32 - 1 >= 0 && y as i32 + 1 < h {
-        neighbors.push((x - 1, y + 1));
-    }
-    if x as i32 + 1 < w && y as i32 + 1 < h {
-        neighbors.push((x + 1, y + 1));
+    if pressed([Scancode::S, Scancode::Down]) {
+        direction.1 += 1.0;
     }
 
-    neighbors
+    direction
 }
 
-fn generate_river(map: &mut Vec<Vec<Cell>>) {
-    let mut rng = rand::rng();
-
-    for y in 0..WORLD_HEIGTH as usize {
-        for x in 0..WORLD_WIDTH as usize {
-            if (matches!(map[y][x].cell_type, CellType::Cliff)
-                || matches!(map[y][x].cell_type, CellType::MediumCliff)
-                || matches!(map[y][x].cell_type, CellType::Mountain)
-                || matches!(map[y][x].cell_type, CellType::MediumMountain)
-                || matches!(map[y][x].cell_type, CellType::HighMountain))
-                && rng.random_bool(0.0008)
-            {
-                let mut x = x * 2 + y % 2;
-                let mut y = y;
-
-                let mut visited = vec![];
-
-                'finish: loop {
-                    map[y][x / 2].cell_type = CellType::River;
-                    visited.push((x, y));
-                    let neighbors_pos = get_neighbors(map, x, y);
-                    if neighbors_pos.len() < 6 {
-                        break;
-                    }
-                    let mut neighbors_pos: Vec<(usize, usize)> = neighbors_pos
-                        .iter()
-                        .filter(|cell_pos| !(visited.contains(cell_pos)))
-                        .cloned()
-                        .collect();
-                    if neighbors_pos.is_empty() {
-                        break;
-                    }
-                    neighbors_pos.shuffle(&mut rng);
-                    let mut min_altitude_cell_pos = neighbors_pos[0];
-                    for cell_pos in neighbors_pos {
-                        if map[cell_pos.1][cell_pos.0 / 2].altitude
-                            < map[min_altitude_cell_pos.1][min_altitude_cell_pos.0 / 2].altitude
-                        {
-                            min_altitude_cell_pos = cell_pos;
-                            break;
-                        }
-
-                        if matches!(map[cell_pos.1][cell_pos.0 / 2].cell_type, CellType::Lake)
-                            || matches!(map[cell_pos.1][cell_pos.0 / 2].cell_type, CellType::Water)
-                            || matches!(
-                                map[cell_pos.1][cell_pos.0 / 2].cell_type,
-                                CellType::ShallowWater
-                            )
-                            || matches!(
-                                map[cell_pos.1][cell_pos.0 / 2].cell_type,
-                                CellType::MediumWater
-                            )
-                            || matches!(
-                                map[cell_pos.1][cell_pos.0 / 2].cell_type,
-                                CellType::DeepWater
-                            )
-                        {
-                            break 'finish;
-                        }
-                    }
+fn open_window(sdl_context: &Sdl) -> (VideoSubsystem, Window, GLContext) {
+    let video_subsystem = sdl_context.video().unwrap();
 
-                    if map[min_altitude_cell_pos.1][min_altitude_cell_pos.0 / 2].altitude
-                        > map[y][x / 2].altitude + 0.04
-                    {
-                        break;
-                    }
+    let gl_attr = video_subsystem.gl_attr();
+    gl_attr.set_context_profile(GLProfile::Core);
+    gl_attr.set_context_version(4, 3);
 
-                    x = min_altitude_cell_pos.0;
-                    y = min_altitude_cell_pos.1;
-                }
-            }
-        }
-    }
-}
+    let window = video_subsystem
+        .window("Civilizations", WINDOW_WIDTH, WINDOW_HEIGHT)
+        .opengl()
+        .position_centered()
+        .build()
+        .unwrap();
 
-fn generate_world() -> Vec<Vec<Cell>> {
-    let time = Instant::now();
-
-    let seed = SystemTime::now()
-        .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-
-    let mut world: Vec<Vec<Cell>> = vec![];
-
-    let altitude_noise = Fbm::<Perlin>::new(seed as u32)
-        .set_octaves(10)
-        .set_frequency(0.2);
-
-    let temperature_noise = Fbm::<Perlin>::new(seed as u32 + 10).set_frequency(0.25);
-
-    let vegetation_noise = Fbm::<Perlin>::new(seed as u32 + 20).set_frequency(0.3);
-    let vegetation_noise = Turbulence::<Fbm<Perlin>, Perlin>::new(vegetation_noise)
-        .set_roughness(20)
-        .set_power(2.0);
-
-    let beach_noise = Fbm::<Perlin>::new(seed as u32 + 30).set_frequency(0.35);
-
-    let cliff_noise = RidgedMulti::<Perlin>::new(seed as u32 + 40)
-        .set_frequency(1.0)
-        .set_attenuation(0.8)
-        .set_persistence(5.0)
-        .set_octaves(10);
-
-    let lake_noise = Fbm::<Perlin>::new(seed as u32 + 50)
-        .set_octaves(10)
-        .set_frequency(0.2);
-
-    for y in 0..WORLD_HEIGTH {
-        let mut row = Vec::new();
-        for x in 0..WORLD_WIDTH {
-            let sx = x as f64 * 0.01 as f64;
-            let sy = y as f64 * 0.01 as f64;
-            let altitude = (altitude_noise.get([sx, sy]) + 1.0) / 2.0;
-            let temp = (temperature_noise.get([sx, sy]) + 1.0) / 2.0;
-            let vegetation = (vegetation_noise.get([sx, sy]) + 1.0) / 2.0;
-            let beach_bias = (beach_noise.get([sx, sy]) + 1.0) / 2.0;
-            let cliff_bias = ((cliff_noise.get([sx, sy]) + 1.0) / 2.0).powf(0.1);
-            let lake_bias = (lake_noise.get([sx, sy]) + 1.0) / 2.0;
-
-            if altitude > 0.85 {
-                row.push(Cell {
-                    cell_type: CellType::Snow,
-                    relative_altitude: altitude - 0.85,
-                    altitude: altitude,
-                });
-            } else if altitude > 0.81 {
-                row.push(Cell {
-                    cell_type: CellType::HighMountain,
-                    relative_altitude: altitude - 0.81,
-                    altitude: altitude,
-                });
-            } else if altitude > 0.8 {
-                row.push(Cell {
-                    cell_type: CellType::MediumMountain,
-                    relative_altitude: altitude - 0.8,
-                    altitude: altitude,
-                });
-            } else if altitude > 0.78 {
-                row.push(Cell {
-                    cell_type: CellType::Mountain,
-                    relative_altitude: altitude - 0.78,
-                    altitude: altitude,
-                });
-            } else if altitude > 0.65 && cliff_bias > 0.95 {
-                row.push(Cell {
-                    cell_type: CellType::MediumCliff,
-                    relative_altitude: altitude - 0.65,
-                    altitude: altitude,
-                });
-            } else if altitude > 0.65 && cliff_bias > 0.8 {
-                row.push(Cell {
-                    cell_type: CellType::Cliff,
-                    relative_altitude: altitude - 0.65,
-                    altitude: altitude,
-                });
-            }
-            // else if altitude > 0.61 && altitude < 0.65 && lake_bias > 0.75 {
-            //     row.push(Cell {
-            //         cell_type: CellType::Lake,
-            //         relative_altitude: altitude - 0.61,
-            //         altitude: altitude,
-            //     });
-            // }
-            else if altitude > 0.6 {
-                if temp > 0.7 {
-                    row.push(Cell {
-                        cell_type: CellType::Sand,
-                        relative_altitude: altitude - 0.6,
-                        altitude: altitude,
-                    });
-                } else if temp > 0.5 {
-                    row.push(Cell {
-                        cell_type: CellType::Dirt,
-                        relative_altitude: altitude - 0.6,
-                        altitude: altitude,
-                    });
-                } else if temp > 0.4 {
-                    if vegetation > 0.6 {
-                        row.push(Cell {
-                            cell_type: CellType::Tree,
-                            relative_altitude: altitude - 0.6,
-                            altitude: altitude,
-                        });
-                    } else {
-                        row.push(Cell {
-                            cell_type: CellType::Grass,
-                            relative_altitude: altitude - 0.6,
-                            altitude: altitude,
-                        });
-                    }
-                } else if temp > 0.3 {
-                    row.push(Cell {
-                        cell_type: CellType::Tundra,
-                        relative_altitude: altitude - 0.6,
-                        altitude: altitude,
-                    });
-                } else {
-                    row.push(Cell {
-                        cell_type: CellType::Snow,
-                        relative_altitude: altitude - 0.6,
-                        altitude: altitude,
-                    });
-                }
-            } else if altitude > 0.59 && beach_bias > 0.65 && temp > 0.4 {
-                row.push(Cell {
-                    cell_type: CellType::Sand,
-                    relative_altitude: altitude - 0.56,
-                    altitude: altitude,
-                });
-            } else if altitude > 0.56 && temp > 0.3 {
-                row.push(Cell {
-                    cell_type: CellType::ShallowWater,
-                    relative_altitude: altitude - 0.56,
-                    altitude: altitude,
-                });
-            } else if altitude > 0.56 {
-                row.push(Cell {
-                    cell_type: CellType::Ice,
-                    relative_altitude: altitude - 0.56,
-                    altitude: altitude,
-                });
-            } else if altitude > 0.52 {
-                row.push(Cell {
-                    cell_type: CellType::Water,
-                    relative_altitude: altitude - 0.52,
-                    altitude: altitude,
-                });
-            } else if altitude > 0.48 {
-                row.push(Cell {
-                    cell_type: CellType::MediumWater,
-                    relative_altitude: altitude - 0.48,
-                    altitude: altitude,
-                });
-            } else {
-                row.push(Cell {
-                    cell_type: CellType::DeepWater,
-                    relative_altitude: altitude,
-                    altitude: altitude,
-                });
-            }
-        }
-        world.push(row);
-    }
+    let gl_context: GLContext = window.gl_create_context().unwrap();
 
-    generate_river(&mut world);
+    gl::load_with(|s| {
+        video_subsystem
+            .gl_get_proc_address(s)
+            .map(|f| f as *const std::ffi::c_void)
+            .unwrap_or(std::ptr::null()) as *const _
+    });
 
-    println!("World generated in: {}s", time.elapsed().as_secs_f32());
+    (video_subsystem, window, gl_context)
+}
 
-    world
+// Generates the same world on the CPU and GPU and compares the cells and the rendered images.
+fn gpu_check(seed: u64, world_size: (i32, i32)) -> Result<(), String> {
+    let sdl_context = sdl3::init().unwrap();
+    let (_video_subsystem, _window, _gl_context) = open_window(&sdl_context);
+
+    let config = WorldGenConfig::new();
+    let noise = WorldNoise::new(seed, &config);
+    let reference: Vec<Vec<Cell>> = (0..world_size.1 as usize)
+        .map(|y| noise.row(y, world_size.0, &config.thresholds))
+        .collect();
+
+    // Start from river colors so cells the shader misses show up as differences.
+    let rivers: Vec<Vec<Cell>> = reference
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|cell| Cell {
+                    cell_type: CellType::River,
+                    ..*cell
+                })
+                .collect()
+        })
+        .collect();
+    let mut buffers = WorldBuffers::new(&rivers);
+
+    let gpu = GpuGenerator::new()?;
+    let map = gpu.generate_cells(seed, &config, &mut buffers)?;
+
+    compare_generation(&reference, &map, buffers.colors())
 }
 
-fn generate_world_colors(map: &Vec<Vec<Cell>>) -> Vec<f32> {
-    let mut colors: Vec<f32> = vec![];
-
-    for y in 0..WORLD_HEIGTH as usize {
-        for x in 0..WORLD_WIDTH as usize {
-            let cell = &map[y][x];
-            let (r, g, b) = cell.get_color();
-            colors.push(r as f32);
-            colors.push(g as f32);
-            colors.push(b as f32);
-            colors.push(255.0);
-        }
+type CellField = (&'static str, fn(&Cell) -> f64);
+
+// Compares generated cells, and the image rendered from their `colors`, to the CPU reference.
+fn compare_generation(
+    reference: &[Vec<Cell>],
+    map: &[Vec<Cell>],
+    colors: &[f32],
+) -> Result<(), String> {
+    let world_size = get_world_size(reference);
+    let fields: [CellField; 7] = [
+        ("altitude", |cell| cell.altitude),
+        ("relative altitude", |cell| cell.relative_altitude),
+        ("temperature", |cell| cell.temperature),
+        ("vegetation", |cell| cell.vegetation),
+        ("beach bias", |cell| cell.beach_bias),
+        ("cliff bias", |cell| cell.cliff_bias),
+        ("lake bias", |cell| cell.lake_bias),
+    ];
+    let cells = || reference.iter().flatten().zip(map.iter().flatten());
+    for (name, field) in fields {
+        let difference = cells()
+            .map(|(cpu, generated)| (field(cpu) - field(generated)).abs())
+            .fold(0.0, f64::max);
+        println!("{:<20}max difference {:e}", name, difference);
     }
 
-    colors
+    let total = (world_size.0 * world_size.1) as f64;
+    let type_mismatches = cells()
+        .filter(|(cpu, generated)| cpu.cell_type != generated.cell_type)
+        .count();
+    println!(
+        "{:<20}{} ({:.4}%)",
+        "type mismatches",
+        type_mismatches,
+        type_mismatches as f64 / total * 100.0
+    );
+
+    let camera = Camera2D::fit_to_world(world_size, (WINDOW_WIDTH, WINDOW_HEIGHT));
+    let view = RasterView {
+        camera: &camera,
+        size: HEX_SIZE,
+        width: WINDOW_WIDTH,
+        height: WINDOW_HEIGHT,
+        clear_color: [0, 0, 0, 255],
+        lighting: &Lighting::new(),
+        grid: false,
+        edge_mode: EdgeMode::None,
+    };
+    let fields = generate_world_fields(reference);
+    let render = |colors: &[f32]| {
+        let world = RasterWorld {
+            size: world_size,
+            colors,
+            fields: &fields,
+            lod: &[],
+        };
+        raster::render(&world, &view)
+    };
+    let cpu_pixels = render(&generate_world_colors(reference));
+    let generated_pixels = render(colors);
+    let pixel_mismatches = cpu_pixels
+        .chunks(4)
+        .zip(generated_pixels.chunks(4))
+        .filter(|(cpu, generated)| {
+            cpu.iter()
+                .zip(generated.iter())
+                .any(|(a, b)| a.abs_diff(*b) > 1)
+        })
+        .count();
+    let pixels = (WINDOW_WIDTH * WINDOW_HEIGHT) as f64;
+    println!(
+        "{:<20}{} ({:.4}%)",
+        "pixel mismatches",
+        pixel_mismatches,
+        pixel_mismatches as f64 / pixels * 100.0
+    );
+
+    if type_mismatches as f64 > total * GPU_CHECK_MAX_TYPE_MISMATCH
+        || pixel_mismatches as f64 > pixels * GPU_CHECK_MAX_PIXEL_MISMATCH
+    {
+        return Err(String::from(
+            "generated world differs from the CPU reference",
+        ));
+    }
+    println!("Generated world matches the CPU reference");
+
+    Ok(())
 }
 
-fn compile_shader(src: &str, shader_type: u32) -> Result<u32, String> {
-    unsafe {
-        let shader = gl::CreateShader(shader_type);
-        let c_str = CString::new(src.as_bytes()).unwrap();
-        gl::ShaderSource(shader, 1, &c_str.as_ptr(), ptr::null());
-        gl::CompileShader(shader);
-
-        let mut success = gl::FALSE as gl::types::GLint;
-        gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut success);
-
-        if success != (gl::TRUE as gl::types::GLint) {
-            let mut len = 0;
-            gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut len);
-            let mut buffer = Vec::with_capacity(len as usize);
-            buffer.set_len((len as usize) - 1);
-            gl::GetShaderInfoLog(shader, len, ptr::null_mut(), buffer.as_mut_ptr() as *mut _);
-            return Err(String::from_utf8_lossy(&buffer).into_owned());
+// Without a world the viewer streams an unbounded chunked world instead. `climate` is the one
+// already applied to `world`.
+fn view(
+    world: Option<World>,
+    seed: u64,
+    climate: Climate,
+    goto: Option<(i32, i32)>,
+    shader_dir: Option<PathBuf>,
+) {
+    let (mut world, mut streaming) = match world {
+        Some(world) => (world, None),
+        None => {
+            let streaming = Streaming::new(seed, &WorldGenConfig::new());
+            (streaming.world(), Some(streaming))
         }
+    };
 
-        Ok(shader)
+    let sdl_context = sdl3::init().unwrap();
+    let (video_subsystem, mut window, _gl_context) = open_window(&sdl_context);
+
+    video_subsystem.text_input().start(&window);
+
+    let world_size = get_world_size(&world.map);
+
+    let mut camera = Camera2D::fit_to_world(world_size, (WINDOW_WIDTH, WINDOW_HEIGHT));
+    let mut navigation = Navigation::new(&camera, world_size);
+    let mut inspector = Inspector::new();
+    let mut display = Display::new();
+    let minimap = Minimap::new(world_size);
+    let mut ui = Ui::new();
+    let mut generator = Generator::new(seed, world_size);
+    generator.config = world.config;
+    generator.climate = climate;
+    generator.streaming = streaming.is_some();
+    if streaming.is_none() {
+        seasons::update_yields(&mut world, &climate);
+    }
+    if let Some((x, y)) = goto {
+        navigation.go_to_cell(&camera, x, y);
     }
-}
 
-fn create_shader_program(vertex_src: &str, fragment_src: &str) -> Result<u32, String> {
-    unsafe {
-        let vertex_shader = compile_shader(vertex_src, gl::VERTEX_SHADER)?;
-        let fragment_shader = compile_shader(fragment_src, gl::FRAGMENT_SHADER)?;
-
-        let program = gl::CreateProgram();
-        gl::AttachShader(program, vertex_shader);
-        gl::AttachShader(program, fragment_shader);
-        gl::LinkProgram(program);
-
-        let mut success = gl::FALSE as gl::types::GLint;
-        gl::GetProgramiv(program, gl::LINK_STATUS, &mut success);
-
-        if success != (gl::TRUE as gl::types::GLint) {
-            let mut len = 0;
-            gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut len);
-            let mut buffer = Vec::with_capacity(len as usize);
-            buffer.set_len((len as usize) - 1);
-            gl::GetProgramInfoLog(program, len, ptr::null_mut(), buffer.as_mut_ptr() as *mut _);
-            return Err(String::from_utf8_lossy(&buffer).into_owned());
+    let mut watcher = shader_dir.map(ShaderWatcher::new);
+    let mut shader_error: Option<String> = None;
+
+    let loaded = watcher
+        .as_ref()
+        .map(|watcher| watcher.load().and_then(|sources| Renderer::new(&sources)));
+    let mut renderer = match loaded {
+        Some(Ok(renderer)) => renderer,
+        Some(Err(e)) => {
+            eprintln!("{}", e);
+            shader_error = Some(e);
+            Renderer::new(&ShaderSources::embedded()).unwrap()
         }
+        None => Renderer::new(&ShaderSources::embedded()).unwrap(),
+    };
 
-        gl::DeleteShader(vertex_shader);
-        gl::DeleteShader(fragment_shader);
+    let mut buffers = WorldBuffers::new(&world.map);
+    let mut dirty = DirtyRegions::new(world_size.0, world_size.1);
 
-        Ok(program)
-    }
-}
+    let gpu = match GpuGenerator::new() {
+        Ok(gpu) => Some(gpu),
+        Err(e) => {
+            eprintln!("GPU generation unavailable: {}", e);
+            None
+        }
+    };
+    generator.gpu_available = gpu.is_some();
 
-fn create_ssbo(data: &Vec<f32>) -> GLuint {
-    unsafe {
-        let mut ssbo: GLuint = 0;
-        gl::GenBuffers(1, &mut ssbo);
-        gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
+    // Layer in the buffers, uploaded again when the selection or the world changes.
+    let mut shown_layer: Option<usize> = None;
+    let mut layers_changed = true;
 
-        gl::BufferData(
-            gl::SHADER_STORAGE_BUFFER,
-            (data.len() * std::mem::size_of::<f32>()) as GLsizeiptr,
-            data.as_ptr() as *const GLvoid,
-            gl::STATIC_DRAW,
-        );
+    let mut events = sdl_context.event_pump().unwrap();
+    let mut timer = FrameTimer::new();
+    let mut title = String::new();
+    let mut status = StatusMessage::new();
+
+    while handle_input(
+        &mut events,
+        &world.map,
+        &mut camera,
+        &mut navigation,
+        &mut inspector,
+        &mut display,
+        &mut ui,
+    ) {
+        let dt = timer.tick();
+        status.update(dt);
+
+        if let Some(watcher) = &mut watcher
+            && watcher.poll()
+        {
+            match watcher.load().and_then(|sources| renderer.reload(&sources)) {
+                Ok(()) => {
+                    status.show(String::from("Shaders reloaded"));
+                    shader_error = None;
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                    shader_error = Some(e);
+                }
+            }
+        }
 
-        gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, 0, ssbo);
+        navigation.update(&mut camera, get_pan_direction(&events), dt);
 
-        ssbo
-    }
-}
+        let mut replaced = false;
+        if generator.take_request() {
+            if let Some(streaming) = &mut streaming {
+                streaming.reset(
+                    generator.seed,
+                    &generator.config,
+                    &mut world.map,
+                    &mut dirty,
+                );
+                world.config = generator.config;
+            } else if let Some(gpu) = &gpu {
+                // The GPU writes the buffers directly, the rivers follow from a worker thread.
+                let time = Instant::now();
+                match gpu.generate(generator.seed, &generator.config, &mut buffers) {
+                    Ok(new_world) => {
+                        status.show(format!(
+                            "World generated on the GPU in: {:.3}s",
+                            time.elapsed().as_secs_f32()
+                        ));
+                        generator.trace_rivers(new_world.map.clone());
+                        world = new_world;
+                        replaced = true;
+                    }
+                    Err(e) => {
+                        // Put the current world back into the buffers the GPU wrote.
+                        eprintln!("{}", e);
+                        dirty.mark_all();
+                    }
+                }
+            }
+        } else if let Some((new_world, rivers_only)) = generator.poll() {
+            world = new_world;
+            replaced = true;
+            if rivers_only {
+                for &(x, y) in world.rivers.iter().flatten() {
+                    dirty.mark(x / 2, y);
+                }
+            } else {
+                dirty.mark_all();
+            }
+        }
+        // Each new day of the season cycle reclassifies the world and its yields.
+        if generator.seasons.advance(dt) && !generator.streaming {
+            generator.climate_changed();
+        }
+        let climate = generator.current_climate();
+        // New worlds come with the generated climate.
+        let reclassified = generator.take_reclassify() || (replaced && climate != Climate::new());
+        if reclassified {
+            let thresholds = world.config.thresholds;
+            reclassify(&mut world, &thresholds, &climate, &mut dirty);
+        }
+        if replaced || reclassified {
+            seasons::update_yields(&mut world, &climate);
+            layers_changed = true;
+        }
+        display.lighting.sea_level = world.config.thresholds.land + generator.climate.sea_level;
+        if let Some(streaming) = &mut streaming {
+            streaming.update(
+                &mut world.map,
+                &mut camera,
+                &mut navigation,
+                &mut inspector,
+                &mut dirty,
+            );
+        }
 
-fn draw(shader_program: u32, camera: &Camera2D) {
-    unsafe {
-        gl::UseProgram(shader_program);
-
-        let camera_zoom_loc =
-            gl::GetUniformLocation(shader_program, b"camera_zoom\0".as_ptr() as *const GLchar);
-        let camera_position_loc = gl::GetUniformLocation(
-            shader_program,
-            b"camera_position\0".as_ptr() as *const GLchar,
-        );
-        let camera_offset_loc =
-            gl::GetUniformLocation(shader_program, b"camera_offset\0".as_ptr() as *const GLchar);
-        let size_loc = gl::GetUniformLocation(shader_program, b"size\0".as_ptr() as *const GLchar);
-        let world_height_loc =
-            gl::GetUniformLocation(shader_program, b"world_height\0".as_ptr() as *const GLchar);
-        let world_width_loc =
-            gl::GetUniformLocation(shader_program, b"world_width\0".as_ptr() as *const GLchar);
-
-        let size: f32 = 20.0;
-
-        gl::Uniform1f(camera_zoom_loc, camera.zoom as f32);
-        gl::Uniform2f(
-            camera_position_loc,
-            camera.position.0 as f32,
-            camera.position.1 as f32,
-        );
-        gl::Uniform2f(
-            camera_offset_loc,
-            camera.offset.0 as f32,
-            camera.offset.1 as f32,
-        );
-        gl::Uniform1f(size_loc, size);
-        gl::Uniform1f(world_height_loc, WORLD_HEIGTH as f32);
-        gl::Uniform1f(world_width_loc, WORLD_WIDTH as f32);
-
-        gl::ClearColor(1.0, 1.0, 1.0, 1.0);
-        gl::Clear(gl::COLOR_BUFFER_BIT);
-
-        let vertices: [f32; 8] = [-1.0, -1.0, 1.0, -1.0, 1.0, 1.0, -1.0, 1.0];
-        let indices: [u32; 6] = [0, 1, 2, 2, 3, 0];
-
-        let mut vao = 0;
-        let mut vbo = 0;
-        let mut ebo = 0;
-
-        gl::GenVertexArrays(1, &mut vao);
-        gl::GenBuffers(1, &mut vbo);
-        gl::GenBuffers(1, &mut ebo);
-
-        gl::BindVertexArray(vao);
-
-        gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
-        gl::BufferData(
-            gl::ARRAY_BUFFER,
-            (vertices.len() * std::mem::size_of::<f32>()) as isize,
-            vertices.as_ptr() as *const _,
-            gl::STATIC_DRAW,
-        );
-
-        gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
-        gl::BufferData(
-            gl::ELEMENT_ARRAY_BUFFER,
-            (indices.len() * std::mem::size_of::<u32>()) as isize,
-            indices.as_ptr() as *const _,
-            gl::STATIC_DRAW,
-        );
-
-        gl::EnableVertexAttribArray(0);
-        gl::VertexAttribPointer(
-            0,
-            2,
-            gl::FLOAT,
-            gl::FALSE,
-            (2 * std::mem::size_of::<f32>()) as i32,
-            std::ptr::null(),
-        );
-
-        gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
-
-        gl::BindVertexArray(0);
-        gl::UseProgram(0);
-    }
-}
+        let screen_size = minimap::screen_size(&camera);
+        ui.begin_frame();
+        if let Some((x, y)) = ui.drag_area(minimap.rect(screen_size)) {
+            navigation.stop(&camera);
+            minimap.jump(&mut camera, x, y);
+        }
+        if display.panels {
+            panels::build(
+                &mut ui,
+                &world,
+                &mut display,
+                &mut inspector,
+                &mut generator,
+                shader_error.as_deref(),
+                screen_size,
+            );
+        }
+        ui.end_frame();
 
-fn handle_input(sdl_context: &sdl3::Sdl, camera: &mut Camera2D) -> bool {
-    let mut events = sdl_context.event_pump().unwrap();
+        let mouse_state = events.mouse_state();
+        inspector.hover(&camera, (mouse_state.x(), mouse_state.y()), world_size);
+        if ui.wants_mouse(mouse_state.x(), mouse_state.y()) {
+            inspector.hovered = None;
+        }
 
-    for event in events.poll_iter() {
-        match event {
-            Event::Quit { .. }
-            | Event::KeyDown {
-                keycode: Some(Keycode::Escape),
-                ..
-            } => return false,
-            Event::MouseWheel {
-                y,
-                mouse_x,
-                mouse_y,
-                ..
-            } => {
-                let mouse_world_pos = camera.get_screen_to_world(mouse_x as f64, mouse_y as f64);
+        let mut new_title = format!("Civilizations{}", display.describe());
+        if display.timing {
+            new_title += &format!(" [{}]", timer.describe());
+        }
+        if generator.seasons.enabled {
+            new_title += &format!(" [{}]", generator.seasons.describe());
+        }
+        if let Some(streaming) = &streaming {
+            new_title += &format!(" [{}]", streaming.describe());
+        }
+        if let Some(e) = &shader_error {
+            new_title += &format!(" [shader error: {}]", e.lines().next().unwrap_or(""));
+        }
+        match (&navigation.goto_input, inspector.title(&world.map)) {
+            (Some(input), _) => new_title += &format!(" - Go to cell (x,y): {}", input),
+            (None, Some(cell)) => new_title += &format!(" - {}", cell),
+            (None, None) => {}
+        }
+        if let Some(message) = navigation.message() {
+            new_title += &format!(" - {}", message);
+        }
+        if let Some(message) = status.text() {
+            new_title += &format!(" - {}", message);
+        }
+        if new_title != title {
+            window.set_title(&new_title).unwrap();
+            title = new_title;
+        }
 
-                camera.zoom = (camera.zoom.ln() + y as f64 * 0.1).exp().clamp(0.01, 5.0);
+        buffers.update(&world.map, &dirty);
+        dirty.clear();
 
-                let mouser_world_pos2 = camera.get_screen_to_world(mouse_x as f64, mouse_y as f64);
+        if display.layer >= world.layers.len() {
+            display.layer = 0;
+        }
+        let layer = (display.overlay == Overlay::Layer).then_some(display.layer);
+        if layers_changed || layer != shown_layer {
+            buffers.upload_layer(layer.and_then(|i| world.layers.at(i)));
+            shown_layer = layer;
+            layers_changed = false;
+        }
 
-                camera.position = (
-                    camera.position.0 + mouse_world_pos.0 - mouser_world_pos2.0,
-                    camera.position.1 + mouse_world_pos.1 - mouser_world_pos2.1,
-                );
-            }
-            Event::MouseMotion {
-                xrel,
-                yrel,
-                mousestate,
-                ..
-            } if mousestate.is_mouse_button_pressed(MouseButton::Left) => {
-                camera.position = (
-                    camera.position.0 - xrel as f64 / camera.zoom,
-                    camera.position.1 - yrel as f64 / camera.zoom,
-                );
-            }
-            _ => {}
+        renderer.draw(&camera, &buffers, &inspector, &display);
+        renderer.draw_minimap(&minimap, &camera, &buffers);
+        if display.timing {
+            renderer.draw_timing(&timer);
         }
+        renderer.draw_ui(&ui, screen_size);
+        window.gl_swap_window();
     }
-
-    true
 }
 
 pub fn main() {
-    let sdl_context = sdl3::init().unwrap();
-    let video_subsystem = sdl_context.video().unwrap();
+    let cli = cli::Cli::parse();
 
-    let gl_attr = video_subsystem.gl_attr();
-    gl_attr.set_context_profile(GLProfile::Core);
-    gl_attr.set_context_version(4, 3);
+    if let Err(e) = cli::run(cli) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
 
-    let window = video_subsystem
-        .window("Civilizations", WINDOW_WIDTH, WINDOW_HEIGHT)
-        .opengl()
-        .position_centered()
-        .build()
-        .unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use civilizations::generate_world_with_config;
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+    use sdl3::video::SwapInterval;
+
+    const BENCH_CHURN: [f64; 3] = [0.01, 0.1, 1.0];
+    const BENCH_FRAMES: u32 = 60;
+
+    // Average milliseconds to upload the changed cells and to upload and draw a frame, for
+    // each share of cells changed per frame in `BENCH_CHURN`.
+    fn bench_updates(mut world: World) -> Vec<(f64, f64)> {
+        let sdl_context = sdl3::init().unwrap();
+        let (video_subsystem, window, _gl_context) = open_window(&sdl_context);
+        video_subsystem
+            .gl_set_swap_interval(SwapInterval::Immediate)
+            .unwrap();
+
+        let world_size = get_world_size(&world.map);
+        let camera = Camera2D::fit_to_world(world_size, (WINDOW_WIDTH, WINDOW_HEIGHT));
+        let inspector = Inspector::new();
+        let display = Display::new();
+        let renderer = Renderer::new(&ShaderSources::embedded()).unwrap();
+
+        let mut buffers = WorldBuffers::new(&world.map);
+        let mut dirty = DirtyRegions::new(world_size.0, world_size.1);
+        let mut events = sdl_context.event_pump().unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        BENCH_CHURN
+            .iter()
+            .map(|churn| {
+                let mut update_time = 0.0;
+                let mut frame_time = 0.0;
+
+                for _ in 0..BENCH_FRAMES {
+                    for (y, row) in world.map.iter_mut().enumerate() {
+                        for (x, cell) in row.iter_mut().enumerate() {
+                            if rng.random_bool(*churn) {
+                                cell.cell_type =
+                                    CellType::ALL[rng.random_range(0..CellType::ALL.len())];
+                                dirty.mark(x, y);
+                            }
+                        }
+                    }
 
-    let _gl_context: GLContext = window.gl_create_context().unwrap();
+                    let time = Instant::now();
+                    buffers.update(&world.map, &dirty);
+                    dirty.clear();
+                    unsafe { gl::Finish() };
+                    update_time += time.elapsed().as_secs_f64();
 
-    gl::load_with(|s| {
-        video_subsystem
-            .gl_get_proc_address(s)
-            .map(|f| f as *const std::ffi::c_void)
-            .unwrap_or(std::ptr::null()) as *const _
-    });
+                    renderer.draw(&camera, &buffers, &inspector, &display);
+                    unsafe { gl::Finish() };
+                    frame_time += time.elapsed().as_secs_f64();
 
-    let mut camera = Camera2D {
-        offset: (WINDOW_WIDTH as f64 / 2.0, WINDOW_HEIGHT as f64 / 2.0),
-        position: (0.0, 0.0),
-        rotation: 0.0,
-        zoom: 1.0,
-    };
+                    window.gl_swap_window();
+                    for _ in events.poll_iter() {}
+                }
+
+                (
+                    update_time * 1000.0 / BENCH_FRAMES as f64,
+                    frame_time * 1000.0 / BENCH_FRAMES as f64,
+                )
+            })
+            .collect()
+    }
 
-    let vertex_src = include_str!("../assets/vertex.glsl");
-    let fragment_src = include_str!("../assets/fragment.glsl");
+    fn reference(seed: u64, world_size: (i32, i32), config: &WorldGenConfig) -> Vec<Vec<Cell>> {
+        let noise = WorldNoise::new(seed, config);
+        (0..world_size.1 as usize)
+            .map(|y| noise.row(y, world_size.0, &config.thresholds))
+            .collect()
+    }
 
-    let shader_program = create_shader_program(vertex_src, fragment_src).unwrap();
+    #[test]
+    fn cpu_generation_matches_the_reference() {
+        // Without rivers the generated world is exactly the noise the GPU check compares to.
+        let mut config = WorldGenConfig::new();
+        config.river_chance = 0.0;
+        let reference = reference(42, (64, 48), &config);
+        let world = generate_world_with_config(42, 64, 48, &config);
 
-    let world = generate_world();
-    let colors = generate_world_colors(&world);
+        assert!(world.map == reference);
+        compare_generation(&reference, &world.map, &generate_world_colors(&world.map)).unwrap();
+    }
 
-    create_ssbo(&colors);
+    #[test]
+    fn differing_generation_is_reported() {
+        let config = WorldGenConfig::new();
+        let reference = reference(42, (64, 48), &config);
+        let mut map = reference.clone();
+        for cell in map[10..20].iter_mut().flatten() {
+            cell.cell_type = CellType::River;
+        }
 
-    while handle_input(&sdl_context, &mut camera) {
-        draw(shader_program, &camera);
-        window.gl_swap_window();
+        assert!(compare_generation(&reference, &map, &generate_world_colors(&map)).is_err());
+    }
+
+    #[test]
+    #[ignore = "needs a window with an OpenGL 4.3 context"]
+    fn gpu_generation_matches_the_cpu() {
+        gpu_check(42, (256, 256)).unwrap();
+    }
+
+    // Run with `--ignored --nocapture` to see the table.
+    #[test]
+    #[ignore = "needs a window with an OpenGL 4.3 context"]
+    fn partial_updates_cut_upload_time() {
+        let world = generate_world_with_config(1, 1000, 1000, &WorldGenConfig::new());
+        let times = bench_updates(world);
+
+        println!("{:>8}{:>14}{:>14}", "churn", "update ms", "frame ms");
+        for (churn, (update, frame)) in BENCH_CHURN.iter().zip(&times) {
+            println!("{:>7}%{:>14.3}{:>14.3}", churn * 100.0, update, frame);
+        }
+
+        assert!(times[0].0 < times[2].0);
+        assert!(times[0].1 < times[2].1);
     }
 }
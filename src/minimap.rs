@@ -0,0 +1,70 @@
+use civilizations::{Camera2D, HEX_SIZE, hex};
+
+const MINIMAP_SIZE: f32 = 240.0;
+const MINIMAP_MARGIN: f32 = 10.0;
+
+pub struct Minimap {
+    pub world_bounds: (f32, f32, f32, f32),
+}
+
+impl Minimap {
+    pub fn new(world_size: (i32, i32)) -> Minimap {
+        Minimap {
+            world_bounds: hex::hex_world_bounds(world_size.0, world_size.1, HEX_SIZE),
+        }
+    }
+
+    // Bottom right corner of the screen as `(x, y, width, height)`, keeping the world aspect.
+    pub fn rect(self: &Self, screen_size: (f32, f32)) -> (f32, f32, f32, f32) {
+        let (min_x, min_y, max_x, max_y) = self.world_bounds;
+        let scale = MINIMAP_SIZE / (max_x - min_x).max(max_y - min_y);
+        let width = (max_x - min_x) * scale;
+        let height = (max_y - min_y) * scale;
+        (
+            screen_size.0 - width - MINIMAP_MARGIN,
+            screen_size.1 - height - MINIMAP_MARGIN,
+            width,
+            height,
+        )
+    }
+
+    pub fn to_world(self: &Self, screen_size: (f32, f32), x: f32, y: f32) -> (f64, f64) {
+        let (rect_x, rect_y, width, height) = self.rect(screen_size);
+        let (min_x, min_y, max_x, max_y) = self.world_bounds;
+        let u = ((x - rect_x) / width).clamp(0.0, 1.0);
+        let v = ((y - rect_y) / height).clamp(0.0, 1.0);
+        (
+            (min_x + u * (max_x - min_x)) as f64,
+            (min_y + v * (max_y - min_y)) as f64,
+        )
+    }
+
+    pub fn to_minimap(self: &Self, screen_size: (f32, f32), world: (f64, f64)) -> (f32, f32) {
+        let (rect_x, rect_y, width, height) = self.rect(screen_size);
+        let (min_x, min_y, max_x, max_y) = self.world_bounds;
+        (
+            rect_x + (world.0 as f32 - min_x) / (max_x - min_x) * width,
+            rect_y + (world.1 as f32 - min_y) / (max_y - min_y) * height,
+        )
+    }
+
+    // Screen corners of the main view projected onto the minimap, clockwise.
+    pub fn viewport(self: &Self, camera: &Camera2D) -> [(f32, f32); 4] {
+        let screen_size = screen_size(camera);
+        [
+            (0.0, 0.0),
+            (screen_size.0 as f64, 0.0),
+            (screen_size.0 as f64, screen_size.1 as f64),
+            (0.0, screen_size.1 as f64),
+        ]
+        .map(|(x, y)| self.to_minimap(screen_size, camera.get_screen_to_world(x, y)))
+    }
+
+    pub fn jump(self: &Self, camera: &mut Camera2D, x: f32, y: f32) {
+        camera.position = self.to_world(screen_size(camera), x, y);
+    }
+}
+
+pub fn screen_size(camera: &Camera2D) -> (f32, f32) {
+    (camera.offset.0 as f32 * 2.0, camera.offset.1 as f32 * 2.0)
+}
@@ -0,0 +1,238 @@
+use std::f64::consts::{PI, TAU};
+
+use civilizations::{Camera2D, HEX_SIZE, hex};
+
+use crate::status::StatusMessage;
+
+const PAN_ACCELERATION: f64 = 4000.0;
+const PAN_MAX_SPEED: f64 = 1500.0;
+const PAN_FRICTION: f64 = 10.0;
+const ZOOM_SPEED: f64 = 12.0;
+const ZOOM_STEP: f64 = 0.1;
+const ZOOM_KEY_STEP: f64 = 0.5;
+const MIN_ZOOM: f64 = 0.01;
+const MAX_ZOOM: f64 = 5.0;
+const TRANSITION_DURATION: f64 = 0.6;
+const BOOKMARK_COUNT: usize = 9;
+
+#[derive(Clone, Copy)]
+pub struct CameraView {
+    pub position: (f64, f64),
+    pub zoom: f64,
+    pub rotation: f64,
+}
+
+impl CameraView {
+    pub fn of(camera: &Camera2D) -> CameraView {
+        CameraView {
+            position: camera.position,
+            zoom: camera.zoom,
+            rotation: camera.rotation,
+        }
+    }
+}
+
+struct Transition {
+    from: CameraView,
+    to: CameraView,
+    elapsed: f64,
+}
+
+fn ease_in_out_cubic(t: f64) -> f64 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+    }
+}
+
+fn lerp(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+fn shortest_angle(from: f64, to: f64) -> f64 {
+    (to - from + PI).rem_euclid(TAU) - PI
+}
+
+pub struct Navigation {
+    velocity: (f64, f64),
+    target_zoom: f64,
+    zoom_anchor: (f64, f64),
+    transition: Option<Transition>,
+    bookmarks: [Option<CameraView>; BOOKMARK_COUNT],
+    world_size: (i32, i32),
+    pub goto_input: Option<String>,
+    message: StatusMessage,
+}
+
+impl Navigation {
+    pub fn new(camera: &Camera2D, world_size: (i32, i32)) -> Navigation {
+        Navigation {
+            velocity: (0.0, 0.0),
+            target_zoom: camera.zoom,
+            zoom_anchor: camera.offset,
+            transition: None,
+            bookmarks: [None; BOOKMARK_COUNT],
+            world_size,
+            goto_input: None,
+            message: StatusMessage::new(),
+        }
+    }
+
+    pub fn zoom_by(self: &mut Self, screen_x: f64, screen_y: f64, steps: f64) {
+        self.transition = None;
+        self.target_zoom = (self.target_zoom.ln() + steps * ZOOM_STEP)
+            .exp()
+            .clamp(MIN_ZOOM, MAX_ZOOM);
+        self.zoom_anchor = (screen_x, screen_y);
+    }
+
+    pub fn zoom_in(self: &mut Self, camera: &Camera2D) {
+        self.zoom_by(camera.offset.0, camera.offset.1, ZOOM_KEY_STEP / ZOOM_STEP);
+    }
+
+    pub fn zoom_out(self: &mut Self, camera: &Camera2D) {
+        self.zoom_by(camera.offset.0, camera.offset.1, -ZOOM_KEY_STEP / ZOOM_STEP);
+    }
+
+    pub fn stop(self: &mut Self, camera: &Camera2D) {
+        self.transition = None;
+        self.target_zoom = camera.zoom;
+    }
+
+    pub fn animate_to(self: &mut Self, camera: &Camera2D, view: CameraView) {
+        self.velocity = (0.0, 0.0);
+        self.transition = Some(Transition {
+            from: CameraView::of(camera),
+            to: view,
+            elapsed: 0.0,
+        });
+    }
+
+    pub fn fit_world(self: &mut Self, camera: &Camera2D) {
+        let screen_size = (
+            (camera.offset.0 * 2.0) as u32,
+            (camera.offset.1 * 2.0) as u32,
+        );
+        let fit = Camera2D::fit_to_world(self.world_size, screen_size);
+        self.animate_to(camera, CameraView::of(&fit));
+    }
+
+    pub fn go_to_cell(self: &mut Self, camera: &Camera2D, x: i32, y: i32) -> bool {
+        if x < 0 || y < 0 || x >= self.world_size.0 || y >= self.world_size.1 {
+            return false;
+        }
+
+        let (px, py) = hex::cell_to_pixel(x as usize, y as usize, HEX_SIZE);
+        self.animate_to(
+            camera,
+            CameraView {
+                position: (px as f64, py as f64),
+                zoom: camera.zoom.max(1.0),
+                rotation: camera.rotation,
+            },
+        );
+        true
+    }
+
+    pub fn submit_goto(self: &mut Self, camera: &Camera2D) {
+        let Some(input) = self.goto_input.take() else {
+            return;
+        };
+
+        let cell: Vec<i32> = input
+            .split(',')
+            .filter_map(|part| part.trim().parse::<i32>().ok())
+            .collect();
+
+        match cell[..] {
+            [x, y] if self.go_to_cell(camera, x, y) => {}
+            _ => self.message.show(format!("Cannot go to cell: {}", input)),
+        }
+    }
+
+    pub fn save_bookmark(self: &mut Self, camera: &Camera2D, slot: usize) {
+        self.bookmarks[slot] = Some(CameraView::of(camera));
+        self.message
+            .show(format!("Camera view saved to bookmark {}", slot + 1));
+    }
+
+    // The last go-to or bookmark message, shown in the window title for a few seconds.
+    pub fn message(self: &Self) -> Option<&str> {
+        self.message.text()
+    }
+
+    pub fn recall_bookmark(self: &mut Self, camera: &Camera2D, slot: usize) {
+        if let Some(view) = self.bookmarks[slot] {
+            self.animate_to(camera, view);
+        }
+    }
+
+    // Keeps transitions and bookmarks on the same place when the world is moved by `offset`
+    // pixels, as the chunked world does when it follows the camera.
+    pub fn shift(self: &mut Self, offset: (f64, f64)) {
+        let shift = |view: &mut CameraView| {
+            view.position = (view.position.0 - offset.0, view.position.1 - offset.1);
+        };
+
+        if let Some(transition) = &mut self.transition {
+            shift(&mut transition.from);
+            shift(&mut transition.to);
+        }
+        for view in self.bookmarks.iter_mut().flatten() {
+            shift(view);
+        }
+    }
+
+    pub fn update(self: &mut Self, camera: &mut Camera2D, pan_direction: (f64, f64), dt: f64) {
+        self.message.update(dt);
+
+        if pan_direction != (0.0, 0.0) {
+            self.transition = None;
+
+            let length =
+                (pan_direction.0 * pan_direction.0 + pan_direction.1 * pan_direction.1).sqrt();
+            self.velocity.0 += pan_direction.0 / length * PAN_ACCELERATION * dt;
+            self.velocity.1 += pan_direction.1 / length * PAN_ACCELERATION * dt;
+
+            let speed =
+                (self.velocity.0 * self.velocity.0 + self.velocity.1 * self.velocity.1).sqrt();
+            if speed > PAN_MAX_SPEED {
+                self.velocity.0 *= PAN_MAX_SPEED / speed;
+                self.velocity.1 *= PAN_MAX_SPEED / speed;
+            }
+        } else {
+            let friction = (-PAN_FRICTION * dt).exp();
+            self.velocity.0 *= friction;
+            self.velocity.1 *= friction;
+        }
+        camera.pan(-self.velocity.0 * dt, -self.velocity.1 * dt);
+
+        if let Some(transition) = &mut self.transition {
+            transition.elapsed += dt;
+
+            let t = ease_in_out_cubic((transition.elapsed / TRANSITION_DURATION).min(1.0));
+            let (from, to) = (transition.from, transition.to);
+
+            camera.position = (
+                lerp(from.position.0, to.position.0, t),
+                lerp(from.position.1, to.position.1, t),
+            );
+            camera.zoom = lerp(from.zoom.ln(), to.zoom.ln(), t).exp();
+            camera.rotation =
+                (from.rotation + shortest_angle(from.rotation, to.rotation) * t).rem_euclid(TAU);
+
+            if transition.elapsed >= TRANSITION_DURATION {
+                self.transition = None;
+            }
+            self.target_zoom = camera.zoom;
+        } else if (camera.zoom - self.target_zoom).abs() > 1e-6 {
+            let t = 1.0 - (-ZOOM_SPEED * dt).exp();
+            camera.zoom_around(
+                self.zoom_anchor.0,
+                self.zoom_anchor.1,
+                lerp(camera.zoom.ln(), self.target_zoom.ln(), t).exp(),
+            );
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use civilizations::{Climate, Overlay, World, WorldGenConfig, config::NoiseLayer, get_world_size};
+
+use crate::{display::Display, generator::Generator, inspector::Inspector, ui::Ui};
+
+const PANEL_MARGIN: f32 = 10.0;
+const DISPLAY_PANEL_WIDTH: f32 = 290.0;
+const INFO_PANEL_WIDTH: f32 = 340.0;
+const GENERATOR_PANEL_WIDTH: f32 = 300.0;
+const TIMING_GRAPH_SPACE: f32 = 100.0;
+const ERROR_COLOR: [f32; 4] = [1.0, 0.45, 0.4, 1.0];
+
+fn display_panel(
+    ui: &mut Ui,
+    world: &World,
+    display: &mut Display,
+    screen_size: (f32, f32),
+) -> f32 {
+    ui.begin_panel(
+        "Display",
+        screen_size.0 - DISPLAY_PANEL_WIDTH - PANEL_MARGIN,
+        PANEL_MARGIN,
+        DISPLAY_PANEL_WIDTH,
+    );
+
+    if ui.button(&format!("Overlay: {:?}", display.overlay)) {
+        display.overlay = display.overlay.next();
+    }
+    if display.overlay == Overlay::Layer {
+        match world.layers.at(display.layer) {
+            Some(layer) => {
+                if ui.button(&format!("Layer: {} ({:?})", layer.name, layer.data.kind())) {
+                    display.layer = (display.layer + 1) % world.layers.len();
+                }
+            }
+            None => ui.label("Layer: none"),
+        }
+    }
+    if ui.button(&format!("Edges: {:?}", display.edge_mode)) {
+        display.edge_mode = display.edge_mode.next();
+    }
+    ui.checkbox("Grid", &mut display.grid);
+    ui.checkbox("Frame timing", &mut display.timing);
+
+    let lighting = &mut display.lighting;
+    ui.checkbox("Hillshade", &mut lighting.hillshade);
+    ui.checkbox("Contours", &mut lighting.contours);
+
+    let mut azimuth = lighting.sun_azimuth.to_degrees();
+    if ui.slider("Sun azimuth", &mut azimuth, 0.0, 360.0) {
+        lighting.sun_azimuth = azimuth.to_radians();
+    }
+    let mut elevation = lighting.sun_elevation.to_degrees();
+    if ui.slider("Sun elevation", &mut elevation, 5.0, 90.0) {
+        lighting.sun_elevation = elevation.to_radians();
+    }
+    ui.slider("Ambient", &mut lighting.ambient, 0.0, 1.0);
+
+    let (panel_x, _, _, _) = ui.end_panel();
+    panel_x
+}
+
+fn layer_sliders(ui: &mut Ui, layer: &mut NoiseLayer) -> bool {
+    let mut changed = ui.int_slider("Octaves", &mut layer.octaves, 1, 12);
+    changed |= ui.slider("Frequency", &mut layer.frequency, 0.01, 2.0);
+    changed |= ui.slider("Lacunarity", &mut layer.lacunarity, 1.0, 4.0);
+    changed |= ui.slider("Persistence", &mut layer.persistence, 0.0, 6.0);
+    changed
+}
+
+fn thresholds_sliders(ui: &mut Ui, generator: &mut Generator) -> bool {
+    let t = &mut generator.config.thresholds;
+    let mut changed = false;
+    for (text, value) in [
+        ("Snow", &mut t.snow),
+        ("High mountain", &mut t.high_mountain),
+        ("Medium mountain", &mut t.medium_mountain),
+        ("Mountain", &mut t.mountain),
+        ("Cliff altitude", &mut t.cliff_altitude),
+        ("Medium cliff bias", &mut t.medium_cliff_bias),
+        ("Cliff bias", &mut t.cliff_bias),
+        ("Land", &mut t.land),
+        ("Sand temperature", &mut t.sand_temperature),
+        ("Dirt temperature", &mut t.dirt_temperature),
+        ("Grass temperature", &mut t.grass_temperature),
+        ("Tree vegetation", &mut t.tree_vegetation),
+        ("Tundra temperature", &mut t.tundra_temperature),
+        ("Beach altitude", &mut t.beach_altitude),
+        ("Beach bias", &mut t.beach_bias),
+        ("Beach temperature", &mut t.beach_temperature),
+        ("Shallow water", &mut t.shallow_water),
+        ("Ice temperature", &mut t.ice_temperature),
+        ("Water", &mut t.water),
+        ("Medium water", &mut t.medium_water),
+    ] {
+        changed |= ui.slider(text, value, 0.0, 1.0);
+    }
+    changed
+}
+
+fn generator_panel(ui: &mut Ui, generator: &mut Generator, x: f32, y: f32) {
+    ui.begin_panel("Generator", x, y, GENERATOR_PANEL_WIDTH);
+
+    ui.label(&format!("Seed: {}", generator.seed));
+    if ui.button("Random seed") {
+        generator.seed = rand::random();
+        generator.changed();
+    }
+    if ui.button(if generator.is_running() {
+        "Generating..."
+    } else {
+        "Regenerate"
+    }) {
+        generator.regenerate();
+    }
+    ui.checkbox("Auto regenerate", &mut generator.auto);
+    if generator.gpu_available && !generator.streaming {
+        ui.checkbox("Generate on GPU", &mut generator.use_gpu);
+    }
+    if ui.button("Reset parameters") {
+        generator.config = WorldGenConfig::new();
+        generator.changed();
+    }
+
+    // Chunks keep their rivers to themselves, so the streamed world can't be reclassified.
+    if !generator.streaming && ui.collapsing("Climate") {
+        let climate = &mut generator.climate;
+        let mut changed = ui.slider("Sea level", &mut climate.sea_level, -0.15, 0.15);
+        changed |= ui.slider(
+            "Temperature offset",
+            &mut climate.temperature_offset,
+            -0.3,
+            0.3,
+        );
+        if ui.button("Reset climate") {
+            *climate = Climate::new();
+            changed = true;
+        }
+
+        let seasons = &mut generator.seasons;
+        changed |= ui.checkbox("Seasons", &mut seasons.enabled);
+        if seasons.enabled {
+            ui.label(&seasons.describe());
+            ui.slider("Days per second", &mut seasons.days_per_second, 0.0, 60.0);
+            changed |= ui.slider("Seasonal swing", &mut seasons.amplitude, 0.0, 0.4);
+        }
+        if changed {
+            generator.climate_changed();
+        }
+    }
+
+    let config = &mut generator.config;
+    let mut changed = false;
+    if ui.collapsing("Sampling") {
+        changed |= ui.slider("Scale", &mut config.scale, 0.001, 0.05);
+    }
+    if ui.collapsing("Altitude") {
+        changed |= layer_sliders(ui, &mut config.altitude);
+    }
+    if ui.collapsing("Temperature") {
+        changed |= layer_sliders(ui, &mut config.temperature);
+    }
+    if ui.collapsing("Vegetation") {
+        changed |= layer_sliders(ui, &mut config.vegetation);
+        changed |= ui.int_slider("Roughness", &mut config.vegetation_roughness, 1, 30);
+        changed |= ui.slider("Power", &mut config.vegetation_power, 0.0, 5.0);
+    }
+    if ui.collapsing("Beaches") {
+        changed |= layer_sliders(ui, &mut config.beach);
+    }
+    if ui.collapsing("Cliffs") {
+        changed |= layer_sliders(ui, &mut config.cliff);
+        changed |= ui.slider("Attenuation", &mut config.cliff_attenuation, 0.0, 4.0);
+        changed |= ui.slider("Exponent", &mut config.cliff_exponent, 0.01, 1.0);
+    }
+    if ui.collapsing("Lakes") {
+        changed |= layer_sliders(ui, &mut config.lake);
+    }
+    if ui.collapsing("Rivers") {
+        changed |= ui.slider("Source chance", &mut config.river_chance, 0.0, 0.005);
+        changed |= ui.slider("Max climb", &mut config.river_climb, 0.0, 0.2);
+    }
+    if ui.collapsing("Biome thresholds") {
+        changed |= thresholds_sliders(ui, generator);
+    }
+    if changed {
+        generator.changed();
+    }
+
+    ui.end_panel();
+}
+
+fn world_panel(ui: &mut Ui, world: &World, y: f32) -> f32 {
+    let (width, height) = get_world_size(&world.map);
+
+    ui.begin_panel("World", PANEL_MARGIN, y, INFO_PANEL_WIDTH);
+    ui.label(&format!(
+        "Size: {}x{} ({} cells)",
+        width,
+        height,
+        width * height
+    ));
+    ui.label(&format!("Rivers: {}", world.rivers.len()));
+    ui.label(&format!(
+        "Layers: {} ({:.1} MiB)",
+        world.layers.len(),
+        world.layers.memory() as f64 / (1024.0 * 1024.0)
+    ));
+    let (_, panel_y, _, panel_height) = ui.end_panel();
+
+    panel_y + panel_height
+}
+
+fn inspector_panel(ui: &mut Ui, world: &World, inspector: &mut Inspector, y: f32) -> f32 {
+    ui.begin_panel("Inspector", PANEL_MARGIN, y, INFO_PANEL_WIDTH);
+
+    match inspector.hovered {
+        Some((x, y)) => ui.label(&format!(
+            "Hovered: ({}, {}) {:?}",
+            x, y, world.map[y][x].cell_type
+        )),
+        None => ui.label("Hovered: -"),
+    }
+
+    match inspector.selected {
+        Some((x, y)) => {
+            let cell = &world.map[y][x];
+            ui.label(&format!("Selected: ({}, {}) {:?}", x, y, cell.cell_type));
+            ui.label(&format!("Altitude: {:.4}", cell.altitude));
+            ui.label(&format!("Relative altitude: {:.4}", cell.relative_altitude));
+            ui.label(&format!("Temperature: {:.4}", cell.temperature));
+            ui.label(&format!("Vegetation: {:.4}", cell.vegetation));
+            ui.label(&format!("Beach bias: {:.4}", cell.beach_bias));
+            ui.label(&format!("Cliff bias: {:.4}", cell.cliff_bias));
+            ui.label(&format!("Lake bias: {:.4}", cell.lake_bias));
+            ui.label(&format!("Movement weight: {}", cell.cell_type.get_weight()));
+            if let Some(i) = world.layers.hex_index(((x * 2 + y % 2) as i32, y as i32)) {
+                for layer in world.layers.iter() {
+                    ui.label(&format!("{}: {}", layer.name, layer.data.value(i)));
+                }
+            }
+            if ui.button("Clear selection") {
+                inspector.selected = None;
+            }
+        }
+        None => ui.label("Click a cell to select it"),
+    }
+
+    let (_, panel_y, _, panel_height) = ui.end_panel();
+    panel_y + panel_height
+}
+
+// Builds this frame's panels; the right columns hold settings, the left one world info.
+pub fn build(
+    ui: &mut Ui,
+    world: &World,
+    display: &mut Display,
+    inspector: &mut Inspector,
+    generator: &mut Generator,
+    shader_error: Option<&str>,
+    screen_size: (f32, f32),
+) {
+    let display_x = display_panel(ui, world, display, screen_size);
+    generator_panel(
+        ui,
+        generator,
+        display_x - GENERATOR_PANEL_WIDTH - PANEL_MARGIN,
+        PANEL_MARGIN,
+    );
+
+    let mut y = PANEL_MARGIN;
+    if display.timing {
+        y += TIMING_GRAPH_SPACE;
+    }
+    y = world_panel(ui, world, y) + PANEL_MARGIN;
+    y = inspector_panel(ui, world, inspector, y) + PANEL_MARGIN;
+
+    if let Some(e) = shader_error {
+        ui.begin_panel("Shader error", PANEL_MARGIN, y, INFO_PANEL_WIDTH);
+        ui.colored_label(e, ERROR_COLOR);
+        ui.end_panel();
+    }
+}
@@ -0,0 +1,560 @@
+use crate::{
+    Camera2D, EdgeMode, hex,
+    lighting::Lighting,
+    lod::{ColorLevel, pack_color},
+    world::FIELD_COUNT,
+};
+
+fn screen_to_world(
+    position: (f32, f32),
+    camera_zoom: f32,
+    camera_position: (f32, f32),
+    camera_offset: (f32, f32),
+    camera_rotation: f32,
+) -> (f32, f32) {
+    let mut x = position.0;
+    let mut y = position.1;
+    x -= camera_offset.0;
+    y -= camera_offset.1;
+    x /= camera_zoom;
+    y /= camera_zoom;
+    let sin = (-camera_rotation).sin();
+    let cos = (-camera_rotation).cos();
+    (x, y) = (x * cos - y * sin, x * sin + y * cos);
+    x -= -camera_position.0;
+    y -= -camera_position.1;
+
+    (x, y)
+}
+
+// Doublewidth offsets of the six neighbors in the shader's order: E, SE, SW, W, NW, NE.
+const NEIGHBORS: [(i32, i32); 6] = [(2, 0), (1, 1), (-1, 1), (-2, 0), (-1, -1), (1, -1)];
+
+const GRID_FADE_START: f32 = 0.25;
+const GRID_FADE_END: f32 = 0.6;
+
+type Color = [f32; 4];
+
+fn mix(a: Color, b: Color, t: f32) -> Color {
+    [0, 1, 2, 3].map(|c| a[c] * (1.0 - t) + b[c] * t)
+}
+
+fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn color_to_unorm8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn unpack_color(packed: u32) -> Color {
+    [0, 1, 2, 3].map(|c| ((packed >> (c * 8)) & 0xff) as f32 / 255.0)
+}
+
+fn hex_edge_distance(point: (f32, f32), size: f32) -> f32 {
+    let (x, y) = (point.0.abs(), point.1.abs());
+    let inner_radius = size * 3.0_f32.sqrt() / 2.0;
+    inner_radius - x.max(0.5 * x + (3.0_f32.sqrt() / 2.0) * y)
+}
+
+// World data the shader reads from its buffers.
+pub struct RasterWorld<'a> {
+    pub size: (i32, i32),
+    pub colors: &'a [f32],
+    // `FIELD_COUNT` values per cell, as made by `generate_world_fields`.
+    pub fields: &'a [f32],
+    // Downsampled colors from `generate_color_pyramid`, empty to always draw full resolution.
+    pub lod: &'a [ColorLevel],
+}
+
+impl RasterWorld<'_> {
+    fn cell_at(self: &Self, doublewidth: (i32, i32)) -> Option<usize> {
+        let (world_width, world_height) = self.size;
+        if doublewidth.0 < 0
+            || doublewidth.1 < 0
+            || doublewidth.0 >= world_width * 2
+            || doublewidth.1 >= world_height
+        {
+            return None;
+        }
+        Some((doublewidth.0 / 2 + doublewidth.1 * world_width) as usize)
+    }
+
+    fn field(self: &Self, index: usize, field: usize) -> f32 {
+        self.fields[index * FIELD_COUNT + field]
+    }
+
+    fn altitude_at(self: &Self, doublewidth: (i32, i32), fallback: f32) -> f32 {
+        match self.cell_at(doublewidth) {
+            Some(index) => self.field(index, 0),
+            None => fallback,
+        }
+    }
+
+    // A packed level is quantized to 8 bits like the shader's `unpackUnorm4x8` sees it.
+    fn lod_color(self: &Self, cell: (i32, i32), level: usize) -> Color {
+        if level == 0 {
+            let i = (cell.0 + cell.1 * self.size.0) as usize * 4;
+            return [0, 1, 2, 3].map(|c| self.colors[i + c] / 255.0);
+        }
+        let lod = &self.lod[level - 1];
+        let i = ((cell.0 >> level) + (cell.1 >> level) * lod.width) as usize * 4;
+        unpack_color(pack_color(&lod.colors[i..i + 4]))
+    }
+
+    // Picks the level whose cells are about one pixel wide and blends towards the next one.
+    fn color(self: &Self, cell: (i32, i32), cell_pixels: f32) -> Color {
+        let levels = self.lod.len();
+        let lod = (-cell_pixels.log2()).clamp(0.0, levels as f32);
+        let level = lod as usize;
+        if level >= levels {
+            return self.lod_color(cell, levels);
+        }
+        mix(
+            self.lod_color(cell, level),
+            self.lod_color(cell, level + 1),
+            lod.fract(),
+        )
+    }
+}
+
+// The camera, output image and display options of a render, the same inputs the viewer
+// hands the shader. Overlays, hovering and selection are viewer state and only the shader
+// draws them.
+pub struct RasterView<'a> {
+    pub camera: &'a Camera2D,
+    pub size: f32,
+    pub width: u32,
+    pub height: u32,
+    pub clear_color: [u8; 4],
+    pub lighting: &'a Lighting,
+    pub grid: bool,
+    pub edge_mode: EdgeMode,
+}
+
+impl RasterView<'_> {
+    // Index into `NEIGHBORS` of the hex side closest to `local`, or `None` when it is more
+    // than `width` screen pixels away.
+    fn nearest_edge(self: &Self, local: (f32, f32), width: f32) -> Option<usize> {
+        let inner_radius = self.size * 3.0_f32.sqrt() / 2.0;
+        let mut nearest = None;
+        let mut nearest_distance = width;
+
+        for (i, neighbor) in NEIGHBORS.iter().enumerate() {
+            let direction = (
+                neighbor.0 as f32 * 3.0_f32.sqrt() / 2.0,
+                neighbor.1 as f32 * 3.0 / 2.0,
+            );
+            let length = (direction.0 * direction.0 + direction.1 * direction.1).sqrt();
+            let dot = (local.0 * direction.0 + local.1 * direction.1) / length;
+            let distance = (inner_radius - dot) * self.camera.zoom as f32;
+            if distance < nearest_distance {
+                nearest = Some(i);
+                nearest_distance = distance;
+            }
+        }
+        nearest
+    }
+
+    fn hillshade_factor(
+        self: &Self,
+        world: &RasterWorld,
+        doublewidth: (i32, i32),
+        altitude: f32,
+    ) -> f32 {
+        let lighting = self.lighting;
+        let sea_level = lighting.sea_level as f32;
+        let neighbors = NEIGHBORS.map(|offset| {
+            world
+                .altitude_at(
+                    (doublewidth.0 + offset.0, doublewidth.1 + offset.1),
+                    altitude,
+                )
+                .max(sea_level)
+        });
+
+        let relief = lighting.relief as f32;
+        let gx = (neighbors[0] - neighbors[3]) / (2.0 * 3.0_f32.sqrt() * self.size);
+        let gy = (neighbors[1] + neighbors[2] - neighbors[4] - neighbors[5]) / (6.0 * self.size);
+        let normal = (-gx * relief, -gy * relief, 1.0);
+        let length = (normal.0 * normal.0 + normal.1 * normal.1 + normal.2 * normal.2).sqrt();
+
+        let sun = lighting.sun_direction();
+        let sun = (sun.0 as f32, sun.1 as f32, sun.2 as f32);
+        let ambient = lighting.ambient as f32;
+        let dot = (normal.0 * sun.0 + normal.1 * sun.1 + normal.2 * sun.2) / length;
+        let light = ambient + (1.0 - ambient) * dot.max(0.0);
+        let flat_light = ambient + (1.0 - ambient) * sun.2.max(0.0);
+        (light / flat_light.max(0.01)).clamp(0.0, 1.5)
+    }
+
+    fn on_contour(
+        self: &Self,
+        world: &RasterWorld,
+        doublewidth: (i32, i32),
+        altitude: f32,
+        local: (f32, f32),
+    ) -> bool {
+        let Some(edge) = self.nearest_edge(local, 1.0) else {
+            return false;
+        };
+
+        let neighbor = (
+            doublewidth.0 + NEIGHBORS[edge].0,
+            doublewidth.1 + NEIGHBORS[edge].1,
+        );
+        let interval = self.lighting.contour_interval as f32;
+        let neighbor_altitude = world.altitude_at(neighbor, altitude);
+        (neighbor_altitude / interval).floor() != (altitude / interval).floor()
+    }
+
+    fn edge_color(
+        self: &Self,
+        world: &RasterWorld,
+        doublewidth: (i32, i32),
+        index: usize,
+        local: (f32, f32),
+    ) -> Color {
+        let width = if self.edge_mode == EdgeMode::Coastlines {
+            2.0
+        } else {
+            1.5
+        };
+        let Some(edge) = self.nearest_edge(local, width) else {
+            return [0.0; 4];
+        };
+        let neighbor = (
+            doublewidth.0 + NEIGHBORS[edge].0,
+            doublewidth.1 + NEIGHBORS[edge].1,
+        );
+        let Some(neighbor) = world.cell_at(neighbor) else {
+            return [0.0; 4];
+        };
+
+        // Fields 6 and 7 are the cell type and whether it is water.
+        match self.edge_mode {
+            EdgeMode::Coastlines if world.field(index, 7) != world.field(neighbor, 7) => {
+                [0.05, 0.1, 0.2, 0.9]
+            }
+            EdgeMode::Biomes if world.field(index, 6) != world.field(neighbor, 6) => {
+                [0.0, 0.0, 0.0, 0.5]
+            }
+            _ => [0.0; 4],
+        }
+    }
+}
+
+pub fn render(world: &RasterWorld, view: &RasterView) -> Vec<u8> {
+    let (world_width, world_height) = world.size;
+    let RasterView {
+        camera,
+        size,
+        width,
+        height,
+        clear_color,
+        lighting,
+        grid,
+        edge_mode,
+    } = *view;
+
+    let camera_zoom = camera.zoom as f32;
+    let camera_position = (camera.position.0 as f32, camera.position.1 as f32);
+    let camera_offset = (camera.offset.0 as f32, camera.offset.1 as f32);
+    let camera_rotation = camera.rotation as f32;
+    let cell_pixels = size * 3.0_f32.sqrt() * camera_zoom;
+
+    let mut pixels: Vec<u8> = Vec::with_capacity(width as usize * height as usize * 4);
+
+    for py in 0..height {
+        for px in 0..width {
+            let cords = (px as f32 + 0.5, py as f32 + 0.5);
+
+            let world_pos = screen_to_world(
+                cords,
+                camera_zoom,
+                camera_position,
+                camera_offset,
+                camera_rotation,
+            );
+
+            let Some((x, y)) = hex::pixel_to_cell(world_pos, size, world_width, world_height)
+            else {
+                pixels.extend_from_slice(&clear_color);
+                continue;
+            };
+
+            let cell = (x as i32, y as i32);
+            let index = x + y * world_width as usize;
+            let mut color = world.color(cell, cell_pixels);
+
+            let doublewidth = (cell.0 * 2 + cell.1 % 2, cell.1);
+            let center =
+                hex::doublewidth_to_pixel((doublewidth.0 as f32, doublewidth.1 as f32), size);
+            let local = (world_pos.0 - center.0, world_pos.1 - center.1);
+            let edge_distance = hex_edge_distance(local, size) * camera_zoom;
+
+            let altitude = world.field(index, 0);
+            if lighting.hillshade {
+                let factor = view.hillshade_factor(
+                    world,
+                    doublewidth,
+                    altitude.max(lighting.sea_level as f32),
+                );
+                color = [
+                    color[0] * factor,
+                    color[1] * factor,
+                    color[2] * factor,
+                    color[3],
+                ];
+            }
+            if lighting.contours && view.on_contour(world, doublewidth, altitude, local) {
+                color = [color[0] * 0.45, color[1] * 0.45, color[2] * 0.45, color[3]];
+            }
+
+            if grid {
+                let fade = smoothstep(GRID_FADE_START, GRID_FADE_END, camera_zoom);
+                let line = 1.0 - smoothstep(0.0, 1.0, edge_distance);
+                let mixed = mix(color, [0.0; 4], 0.35 * fade * line);
+                color = [mixed[0], mixed[1], mixed[2], color[3]];
+            }
+            if edge_mode != EdgeMode::None {
+                let edge = view.edge_color(world, doublewidth, index, local);
+                let mixed = mix(color, edge, edge[3]);
+                color = [mixed[0], mixed[1], mixed[2], color[3]];
+            }
+
+            pixels.extend(color.map(color_to_unorm8));
+        }
+    }
+
+    pixels
+}
+
+pub fn pick_cell(
+    screen: (f64, f64),
+    camera: &Camera2D,
+    size: f32,
+    world_width: i32,
+    world_height: i32,
+) -> Option<(usize, usize)> {
+    let cords = (screen.0.floor() as f32 + 0.5, screen.1.floor() as f32 + 0.5);
+
+    let world_pos = screen_to_world(
+        cords,
+        camera.zoom as f32,
+        (camera.position.0 as f32, camera.position.1 as f32),
+        (camera.offset.0 as f32, camera.offset.1 as f32),
+        camera.rotation as f32,
+    );
+
+    hex::pixel_to_cell(world_pos, size, world_width, world_height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        HEX_SIZE, generate_world, generate_world_colors, generate_world_fields, get_world_size,
+        lod::generate_color_pyramid,
+    };
+
+    // FNV-1a, so golden hashes do not change with the standard library's hasher.
+    fn pixel_hash(pixels: &[u8]) -> u64 {
+        pixels.iter().fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ *byte as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    fn plain_view<'a>(
+        camera: &'a Camera2D,
+        lighting: &'a Lighting,
+        size: (u32, u32),
+    ) -> RasterView<'a> {
+        RasterView {
+            camera,
+            size: HEX_SIZE,
+            width: size.0,
+            height: size.1,
+            clear_color: [0, 0, 0, 0],
+            lighting,
+            grid: false,
+            edge_mode: EdgeMode::None,
+        }
+    }
+
+    // Every cell at `altitude` with its own color.
+    fn test_world(world_size: (i32, i32), altitude: f32) -> (Vec<f32>, Vec<f32>) {
+        let cells = (world_size.0 * world_size.1) as usize;
+        let colors = (0..cells)
+            .flat_map(|i| {
+                [
+                    (i * 10 % 256) as f32,
+                    255.0 - (i * 10 % 256) as f32,
+                    128.0,
+                    255.0,
+                ]
+            })
+            .collect();
+        let fields = (0..cells)
+            .flat_map(|i| [altitude, 0.5, 0.5, 0.0, 0.0, 0.0, (i % 3) as f32, 0.0])
+            .collect();
+        (colors, fields)
+    }
+
+    #[test]
+    fn cell_centers_have_their_cell_color() {
+        let world_size = (5, 4);
+        let (colors, fields) = test_world(world_size, 0.7);
+        let world = RasterWorld {
+            size: world_size,
+            colors: &colors,
+            fields: &fields,
+            lod: &[],
+        };
+        let camera = Camera2D::fit_to_world(world_size, (160, 120));
+        let lighting = Lighting::new();
+        let pixels = render(&world, &plain_view(&camera, &lighting, (160, 120)));
+
+        for y in 0..world_size.1 as usize {
+            for x in 0..world_size.0 as usize {
+                let center = hex::cell_to_pixel(x, y, HEX_SIZE);
+                let screen = camera.get_world_to_screen(center.0 as f64, center.1 as f64);
+                let i = (screen.0 as usize + screen.1 as usize * 160) * 4;
+                let j = (x + y * world_size.0 as usize) * 4;
+                let expected: Vec<u8> = colors[j..j + 4].iter().map(|c| *c as u8).collect();
+                assert_eq!(pixels[i..i + 4], expected[..], "cell {} {}", x, y);
+            }
+        }
+        assert_eq!(pixels[0..4], [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn zoomed_out_views_draw_the_matching_level() {
+        let world_size = (64, 64);
+        let (colors, fields) = test_world(world_size, 0.7);
+        let lod = generate_color_pyramid(&colors, world_size.0, world_size.1);
+        let world = RasterWorld {
+            size: world_size,
+            colors: &colors,
+            fields: &fields,
+            lod: &lod,
+        };
+
+        // Cells a quarter pixel wide fall exactly on level 2.
+        let mut camera = Camera2D::fit_to_world(world_size, (32, 32));
+        camera.zoom = 0.25 / (HEX_SIZE as f64 * 3.0_f64.sqrt());
+        let lighting = Lighting::new();
+        let pixels = render(&world, &plain_view(&camera, &lighting, (32, 32)));
+
+        let world_pos = screen_to_world(
+            (16.5, 16.5),
+            camera.zoom as f32,
+            (camera.position.0 as f32, camera.position.1 as f32),
+            (camera.offset.0 as f32, camera.offset.1 as f32),
+            0.0,
+        );
+        let (x, y) = hex::pixel_to_cell(world_pos, HEX_SIZE, 64, 64).unwrap();
+        let level = &lod[1];
+        let j = ((x >> 2) + (y >> 2) * level.width as usize) * 4;
+        let expected = pack_color(&level.colors[j..j + 4]).to_le_bytes();
+        let i = (16 + 16 * 32) * 4;
+        assert_eq!(pixels[i..i + 4], expected);
+    }
+
+    #[test]
+    fn flat_ground_keeps_its_color_under_hillshade() {
+        let world_size = (6, 5);
+        let (colors, fields) = test_world(world_size, 0.7);
+        let world = RasterWorld {
+            size: world_size,
+            colors: &colors,
+            fields: &fields,
+            lod: &[],
+        };
+        let camera = Camera2D::fit_to_world(world_size, (120, 90));
+        let plain = render(&world, &plain_view(&camera, &Lighting::new(), (120, 90)));
+
+        let mut lighting = Lighting::new();
+        lighting.hillshade = true;
+        lighting.contours = true;
+        let shaded = render(&world, &plain_view(&camera, &lighting, (120, 90)));
+
+        assert_eq!(plain, shaded);
+    }
+
+    #[test]
+    fn grid_darkens_cell_borders_only() {
+        let world_size = (3, 3);
+        let (colors, fields) = test_world(world_size, 0.7);
+        let world = RasterWorld {
+            size: world_size,
+            colors: &colors,
+            fields: &fields,
+            lod: &[],
+        };
+        let camera = Camera2D::fit_to_world(world_size, (200, 160));
+        let lighting = Lighting::new();
+        let plain = render(&world, &plain_view(&camera, &lighting, (200, 160)));
+        let grid = render(
+            &world,
+            &RasterView {
+                grid: true,
+                ..plain_view(&camera, &lighting, (200, 160))
+            },
+        );
+
+        let center = hex::cell_to_pixel(1, 1, HEX_SIZE);
+        let screen = camera.get_world_to_screen(center.0 as f64, center.1 as f64);
+        let i = (screen.0 as usize + screen.1 as usize * 200) * 4;
+        assert_eq!(plain[i..i + 4], grid[i..i + 4]);
+
+        let darker = plain
+            .chunks(4)
+            .zip(grid.chunks(4))
+            .filter(|(plain, grid)| grid[0] < plain[0] || grid[1] < plain[1])
+            .count();
+        assert!(darker > 0);
+        assert!(
+            grid.chunks(4)
+                .zip(plain.chunks(4))
+                .all(|(grid, plain)| grid <= plain)
+        );
+    }
+
+    #[test]
+    fn golden_render() {
+        let world = generate_world(42, 32, 24);
+        let world_size = get_world_size(&world.map);
+        let colors = generate_world_colors(&world.map);
+        let fields = generate_world_fields(&world.map);
+        let lod = generate_color_pyramid(&colors, world_size.0, world_size.1);
+        let world = RasterWorld {
+            size: world_size,
+            colors: &colors,
+            fields: &fields,
+            lod: &lod,
+        };
+        let camera = Camera2D::fit_to_world(world_size, (128, 96));
+        let lighting = Lighting::new();
+        let view = RasterView {
+            clear_color: [0, 0, 0, 255],
+            ..plain_view(&camera, &lighting, (128, 96))
+        };
+        let pixels = render(&world, &view);
+
+        assert_eq!(pixels.len(), 128 * 96 * 4);
+        assert_eq!(pixel_hash(&pixels), 0x3d02_fac6_9418_8f5a);
+
+        let mut lighting = Lighting::new();
+        lighting.hillshade = true;
+        lighting.contours = true;
+        let view = RasterView {
+            lighting: &lighting,
+            grid: true,
+            edge_mode: EdgeMode::Coastlines,
+            ..view
+        };
+        assert_eq!(pixel_hash(&render(&world, &view)), 0x9635_026f_52f5_abe9);
+    }
+}
@@ -0,0 +1,663 @@
+use std::{ffi::CString, ptr};
+
+use civilizations::{Camera2D, HEX_SIZE};
+use gl::types::{GLint, GLuint};
+
+use crate::{
+    display::Display,
+    font::{self, ATLAS_HEIGHT, ATLAS_WIDTH},
+    frame_timer::{FRAME_SAMPLES, FrameTimer},
+    inspector::Inspector,
+    minimap::{self, Minimap},
+    ui::Ui,
+    world_buffers::WorldBuffers,
+};
+
+pub const VERTEX_SRC: &str = include_str!("../assets/vertex.glsl");
+pub const FRAGMENT_SRC: &str = include_str!("../assets/fragment.glsl");
+pub const TIMING_SRC: &str = include_str!("../assets/timing.glsl");
+pub const MINIMAP_SRC: &str = include_str!("../assets/minimap.glsl");
+pub const UI_VERTEX_SRC: &str = include_str!("../assets/ui_vertex.glsl");
+pub const UI_SRC: &str = include_str!("../assets/ui.glsl");
+
+// Position, texture coordinates and color of each UI vertex.
+const UI_VERTEX_FLOATS: usize = 8;
+
+pub struct ShaderSources {
+    pub vertex: String,
+    pub fragment: String,
+    pub timing: String,
+    pub minimap: String,
+    pub ui_vertex: String,
+    pub ui: String,
+}
+
+impl ShaderSources {
+    pub fn embedded() -> ShaderSources {
+        ShaderSources {
+            vertex: String::from(VERTEX_SRC),
+            fragment: String::from(FRAGMENT_SRC),
+            timing: String::from(TIMING_SRC),
+            minimap: String::from(MINIMAP_SRC),
+            ui_vertex: String::from(UI_VERTEX_SRC),
+            ui: String::from(UI_SRC),
+        }
+    }
+}
+
+const TIMING_GRAPH_RECT: (f32, f32, f32, f32) = (10.0, 10.0, 360.0, 90.0);
+const TIMING_GRAPH_SCALE: f32 = 50.0;
+
+pub fn compile_shader(src: &str, shader_type: u32) -> Result<u32, String> {
+    let c_str = CString::new(src.as_bytes()).map_err(|e| e.to_string())?;
+    unsafe {
+        let shader = gl::CreateShader(shader_type);
+        gl::ShaderSource(shader, 1, &c_str.as_ptr(), ptr::null());
+        gl::CompileShader(shader);
+
+        let mut success = gl::FALSE as gl::types::GLint;
+        gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut success);
+
+        if success != (gl::TRUE as gl::types::GLint) {
+            let mut len = 0;
+            gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut len);
+            // The log length counts the trailing nul, and is 0 when there is no log at all.
+            let mut buffer: Vec<u8> = vec![0; len.max(0) as usize];
+            gl::GetShaderInfoLog(shader, len, ptr::null_mut(), buffer.as_mut_ptr() as *mut _);
+            buffer.pop();
+            gl::DeleteShader(shader);
+            return Err(String::from_utf8_lossy(&buffer).into_owned());
+        }
+
+        Ok(shader)
+    }
+}
+
+pub fn create_shader_program(vertex_src: &str, fragment_src: &str) -> Result<u32, String> {
+    unsafe {
+        let vertex_shader = compile_shader(vertex_src, gl::VERTEX_SHADER)
+            .map_err(|e| format!("vertex shader: {}", e))?;
+        let fragment_shader = match compile_shader(fragment_src, gl::FRAGMENT_SHADER) {
+            Ok(fragment_shader) => fragment_shader,
+            Err(e) => {
+                gl::DeleteShader(vertex_shader);
+                return Err(format!("fragment shader: {}", e));
+            }
+        };
+
+        let program = gl::CreateProgram();
+        gl::AttachShader(program, vertex_shader);
+        gl::AttachShader(program, fragment_shader);
+        gl::LinkProgram(program);
+
+        let mut success = gl::FALSE as gl::types::GLint;
+        gl::GetProgramiv(program, gl::LINK_STATUS, &mut success);
+
+        if success != (gl::TRUE as gl::types::GLint) {
+            let mut len = 0;
+            gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut len);
+            let mut buffer: Vec<u8> = vec![0; len.max(0) as usize];
+            gl::GetProgramInfoLog(program, len, ptr::null_mut(), buffer.as_mut_ptr() as *mut _);
+            buffer.pop();
+            gl::DeleteProgram(program);
+            gl::DeleteShader(vertex_shader);
+            gl::DeleteShader(fragment_shader);
+            return Err(format!("link: {}", String::from_utf8_lossy(&buffer)));
+        }
+
+        gl::DeleteShader(vertex_shader);
+        gl::DeleteShader(fragment_shader);
+
+        Ok(program)
+    }
+}
+
+pub fn create_compute_program(compute_src: &str) -> Result<u32, String> {
+    unsafe {
+        let compute_shader = compile_shader(compute_src, gl::COMPUTE_SHADER)
+            .map_err(|e| format!("compute shader: {}", e))?;
+
+        let program = gl::CreateProgram();
+        gl::AttachShader(program, compute_shader);
+        gl::LinkProgram(program);
+        gl::DeleteShader(compute_shader);
+
+        let mut success = gl::FALSE as gl::types::GLint;
+        gl::GetProgramiv(program, gl::LINK_STATUS, &mut success);
+
+        if success != (gl::TRUE as gl::types::GLint) {
+            let mut len = 0;
+            gl::GetProgramiv(program, gl::INFO_LOG_LENGTH, &mut len);
+            let mut buffer: Vec<u8> = vec![0; len.max(0) as usize];
+            gl::GetProgramInfoLog(program, len, ptr::null_mut(), buffer.as_mut_ptr() as *mut _);
+            buffer.pop();
+            gl::DeleteProgram(program);
+            return Err(format!("link: {}", String::from_utf8_lossy(&buffer)));
+        }
+
+        Ok(program)
+    }
+}
+
+pub fn uniform_location(program: GLuint, name: &str) -> GLint {
+    let name = CString::new(name).unwrap();
+    unsafe { gl::GetUniformLocation(program, name.as_ptr()) }
+}
+
+struct WorldUniforms {
+    camera_zoom: GLint,
+    camera_position: GLint,
+    camera_offset: GLint,
+    camera_rotation: GLint,
+    size: GLint,
+    world_height: GLint,
+    world_width: GLint,
+    hovered_cell: GLint,
+    selected_cell: GLint,
+    overlay: GLint,
+    layer_kind: GLint,
+    layer_range: GLint,
+    hillshade: GLint,
+    contours: GLint,
+    sun_direction: GLint,
+    ambient: GLint,
+    relief: GLint,
+    sea_level: GLint,
+    contour_interval: GLint,
+    grid: GLint,
+    edge_mode: GLint,
+    lod_levels: GLint,
+    lod_offsets: GLint,
+    lod_widths: GLint,
+}
+
+impl WorldUniforms {
+    fn new(program: GLuint) -> WorldUniforms {
+        WorldUniforms {
+            camera_zoom: uniform_location(program, "camera_zoom"),
+            camera_position: uniform_location(program, "camera_position"),
+            camera_offset: uniform_location(program, "camera_offset"),
+            camera_rotation: uniform_location(program, "camera_rotation"),
+            size: uniform_location(program, "size"),
+            world_height: uniform_location(program, "world_height"),
+            world_width: uniform_location(program, "world_width"),
+            hovered_cell: uniform_location(program, "hovered_cell"),
+            selected_cell: uniform_location(program, "selected_cell"),
+            overlay: uniform_location(program, "overlay"),
+            layer_kind: uniform_location(program, "layer_kind"),
+            layer_range: uniform_location(program, "layer_range"),
+            hillshade: uniform_location(program, "hillshade"),
+            contours: uniform_location(program, "contours"),
+            sun_direction: uniform_location(program, "sun_direction"),
+            ambient: uniform_location(program, "ambient"),
+            relief: uniform_location(program, "relief"),
+            sea_level: uniform_location(program, "sea_level"),
+            contour_interval: uniform_location(program, "contour_interval"),
+            grid: uniform_location(program, "grid"),
+            edge_mode: uniform_location(program, "edge_mode"),
+            lod_levels: uniform_location(program, "lod_levels"),
+            lod_offsets: uniform_location(program, "lod_offsets"),
+            lod_widths: uniform_location(program, "lod_widths"),
+        }
+    }
+}
+
+struct TimingUniforms {
+    frame_times: GLint,
+    frame_count: GLint,
+    graph_rect: GLint,
+    graph_scale: GLint,
+}
+
+impl TimingUniforms {
+    fn new(program: GLuint) -> TimingUniforms {
+        TimingUniforms {
+            frame_times: uniform_location(program, "frame_times"),
+            frame_count: uniform_location(program, "frame_count"),
+            graph_rect: uniform_location(program, "graph_rect"),
+            graph_scale: uniform_location(program, "graph_scale"),
+        }
+    }
+}
+
+// World, timing, minimap and UI programs, deleting the ones already built when one fails.
+fn create_programs(sources: &ShaderSources) -> Result<[GLuint; 4], String> {
+    let stages = [
+        ("fragment.glsl", &sources.vertex, &sources.fragment),
+        ("timing.glsl", &sources.vertex, &sources.timing),
+        ("minimap.glsl", &sources.vertex, &sources.minimap),
+        ("ui.glsl", &sources.ui_vertex, &sources.ui),
+    ];
+
+    let mut programs = [0; 4];
+    for (i, (file, vertex_src, fragment_src)) in stages.into_iter().enumerate() {
+        match create_shader_program(vertex_src, fragment_src) {
+            Ok(program) => programs[i] = program,
+            Err(e) => {
+                for program in &programs[..i] {
+                    unsafe { gl::DeleteProgram(*program) };
+                }
+                return Err(format!("{}: {}", file, e));
+            }
+        }
+    }
+    Ok(programs)
+}
+
+struct MinimapUniforms {
+    world_height: GLint,
+    world_width: GLint,
+    size: GLint,
+    lod_levels: GLint,
+    lod_offsets: GLint,
+    lod_widths: GLint,
+    minimap_rect: GLint,
+    world_bounds: GLint,
+    viewport: GLint,
+}
+
+impl MinimapUniforms {
+    fn new(program: GLuint) -> MinimapUniforms {
+        MinimapUniforms {
+            world_height: uniform_location(program, "world_height"),
+            world_width: uniform_location(program, "world_width"),
+            size: uniform_location(program, "size"),
+            lod_levels: uniform_location(program, "lod_levels"),
+            lod_offsets: uniform_location(program, "lod_offsets"),
+            lod_widths: uniform_location(program, "lod_widths"),
+            minimap_rect: uniform_location(program, "minimap_rect"),
+            world_bounds: uniform_location(program, "world_bounds"),
+            viewport: uniform_location(program, "viewport"),
+        }
+    }
+}
+
+struct UiUniforms {
+    screen_size: GLint,
+}
+
+impl UiUniforms {
+    fn new(program: GLuint) -> UiUniforms {
+        UiUniforms {
+            screen_size: uniform_location(program, "screen_size"),
+        }
+    }
+}
+
+pub struct Renderer {
+    program: GLuint,
+    uniforms: WorldUniforms,
+    timing_program: GLuint,
+    timing_uniforms: TimingUniforms,
+    minimap_program: GLuint,
+    minimap_uniforms: MinimapUniforms,
+    ui_program: GLuint,
+    ui_uniforms: UiUniforms,
+    vao: GLuint,
+    vbo: GLuint,
+    ebo: GLuint,
+    ui_vao: GLuint,
+    ui_vbo: GLuint,
+    font_texture: GLuint,
+}
+
+impl Renderer {
+    pub fn new(sources: &ShaderSources) -> Result<Renderer, String> {
+        let [program, timing_program, minimap_program, ui_program] = create_programs(sources)?;
+
+        let vertices: [f32; 8] = [-1.0, -1.0, 1.0, -1.0, 1.0, 1.0, -1.0, 1.0];
+        let indices: [u32; 6] = [0, 1, 2, 2, 3, 0];
+
+        let mut vao = 0;
+        let mut vbo = 0;
+        let mut ebo = 0;
+        let mut ui_vao = 0;
+        let mut ui_vbo = 0;
+        let mut font_texture = 0;
+
+        unsafe {
+            gl::GenVertexArrays(1, &mut vao);
+            gl::GenBuffers(1, &mut vbo);
+            gl::GenBuffers(1, &mut ebo);
+
+            gl::BindVertexArray(vao);
+
+            gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                (vertices.len() * std::mem::size_of::<f32>()) as isize,
+                vertices.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+
+            gl::BindBuffer(gl::ELEMENT_ARRAY_BUFFER, ebo);
+            gl::BufferData(
+                gl::ELEMENT_ARRAY_BUFFER,
+                (indices.len() * std::mem::size_of::<u32>()) as isize,
+                indices.as_ptr() as *const _,
+                gl::STATIC_DRAW,
+            );
+
+            gl::EnableVertexAttribArray(0);
+            gl::VertexAttribPointer(
+                0,
+                2,
+                gl::FLOAT,
+                gl::FALSE,
+                (2 * std::mem::size_of::<f32>()) as i32,
+                std::ptr::null(),
+            );
+
+            gl::GenVertexArrays(1, &mut ui_vao);
+            gl::GenBuffers(1, &mut ui_vbo);
+
+            gl::BindVertexArray(ui_vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, ui_vbo);
+
+            let stride = (UI_VERTEX_FLOATS * std::mem::size_of::<f32>()) as i32;
+            for (location, size, offset) in [(0, 2, 0), (1, 2, 2), (2, 4, 4)] {
+                gl::EnableVertexAttribArray(location);
+                gl::VertexAttribPointer(
+                    location,
+                    size,
+                    gl::FLOAT,
+                    gl::FALSE,
+                    stride,
+                    (offset * std::mem::size_of::<f32>()) as *const _,
+                );
+            }
+
+            gl::BindVertexArray(0);
+
+            let atlas = font::atlas();
+            gl::GenTextures(1, &mut font_texture);
+            gl::BindTexture(gl::TEXTURE_2D, font_texture);
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::R8 as i32,
+                ATLAS_WIDTH as i32,
+                ATLAS_HEIGHT as i32,
+                0,
+                gl::RED,
+                gl::UNSIGNED_BYTE,
+                atlas.as_ptr() as *const _,
+            );
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as i32);
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as i32);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+
+        Ok(Renderer {
+            program,
+            uniforms: WorldUniforms::new(program),
+            timing_program,
+            timing_uniforms: TimingUniforms::new(timing_program),
+            minimap_program,
+            minimap_uniforms: MinimapUniforms::new(minimap_program),
+            ui_program,
+            ui_uniforms: UiUniforms::new(ui_program),
+            vao,
+            vbo,
+            ebo,
+            ui_vao,
+            ui_vbo,
+            font_texture,
+        })
+    }
+
+    // Keeps the current programs when the new sources fail to compile or link.
+    pub fn reload(self: &mut Self, sources: &ShaderSources) -> Result<(), String> {
+        let [program, timing_program, minimap_program, ui_program] = create_programs(sources)?;
+
+        unsafe {
+            gl::DeleteProgram(self.program);
+            gl::DeleteProgram(self.timing_program);
+            gl::DeleteProgram(self.minimap_program);
+            gl::DeleteProgram(self.ui_program);
+        }
+
+        self.program = program;
+        self.uniforms = WorldUniforms::new(program);
+        self.timing_program = timing_program;
+        self.timing_uniforms = TimingUniforms::new(timing_program);
+        self.minimap_program = minimap_program;
+        self.minimap_uniforms = MinimapUniforms::new(minimap_program);
+        self.ui_program = ui_program;
+        self.ui_uniforms = UiUniforms::new(ui_program);
+
+        Ok(())
+    }
+
+    pub fn draw(
+        self: &Self,
+        camera: &Camera2D,
+        buffers: &WorldBuffers,
+        inspector: &Inspector,
+        display: &Display,
+    ) {
+        let uniforms = &self.uniforms;
+        let world_size = buffers.world_size;
+
+        unsafe {
+            gl::UseProgram(self.program);
+
+            gl::Uniform1f(uniforms.camera_zoom, camera.zoom as f32);
+            gl::Uniform2f(
+                uniforms.camera_position,
+                camera.position.0 as f32,
+                camera.position.1 as f32,
+            );
+            gl::Uniform2f(
+                uniforms.camera_offset,
+                camera.offset.0 as f32,
+                camera.offset.1 as f32,
+            );
+            gl::Uniform1f(uniforms.camera_rotation, camera.rotation as f32);
+            gl::Uniform1f(uniforms.size, HEX_SIZE);
+            gl::Uniform1f(uniforms.world_height, world_size.1 as f32);
+            gl::Uniform1f(uniforms.world_width, world_size.0 as f32);
+
+            gl::Uniform1i(uniforms.lod_levels, buffers.lod_levels);
+            gl::Uniform1iv(
+                uniforms.lod_offsets,
+                buffers.lod_offsets.len() as i32,
+                buffers.lod_offsets.as_ptr(),
+            );
+            gl::Uniform1iv(
+                uniforms.lod_widths,
+                buffers.lod_widths.len() as i32,
+                buffers.lod_widths.as_ptr(),
+            );
+
+            let (hovered_x, hovered_y) = inspector
+                .hovered
+                .map_or((-1, -1), |(x, y)| (x as i32, y as i32));
+            gl::Uniform2i(uniforms.hovered_cell, hovered_x, hovered_y);
+            let (selected_x, selected_y) = inspector
+                .selected
+                .map_or((-1, -1), |(x, y)| (x as i32, y as i32));
+            gl::Uniform2i(uniforms.selected_cell, selected_x, selected_y);
+            gl::Uniform1i(uniforms.overlay, display.overlay as i32);
+            gl::Uniform1i(uniforms.layer_kind, buffers.layer_kind);
+            gl::Uniform2f(
+                uniforms.layer_range,
+                buffers.layer_range.0,
+                buffers.layer_range.1,
+            );
+            gl::Uniform1i(uniforms.grid, display.grid as i32);
+            gl::Uniform1i(uniforms.edge_mode, display.edge_mode as i32);
+
+            let lighting = &display.lighting;
+            let sun_direction = lighting.sun_direction();
+            gl::Uniform1i(uniforms.hillshade, lighting.hillshade as i32);
+            gl::Uniform1i(uniforms.contours, lighting.contours as i32);
+            gl::Uniform3f(
+                uniforms.sun_direction,
+                sun_direction.0 as f32,
+                sun_direction.1 as f32,
+                sun_direction.2 as f32,
+            );
+            gl::Uniform1f(uniforms.ambient, lighting.ambient as f32);
+            gl::Uniform1f(uniforms.relief, lighting.relief as f32);
+            gl::Uniform1f(uniforms.sea_level, lighting.sea_level as f32);
+            gl::Uniform1f(uniforms.contour_interval, lighting.contour_interval as f32);
+
+            gl::ClearColor(1.0, 1.0, 1.0, 1.0);
+            gl::Clear(gl::COLOR_BUFFER_BIT);
+
+            gl::BindVertexArray(self.vao);
+            gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+
+            gl::BindVertexArray(0);
+            gl::UseProgram(0);
+        }
+    }
+
+    pub fn draw_minimap(self: &Self, minimap: &Minimap, camera: &Camera2D, buffers: &WorldBuffers) {
+        let uniforms = &self.minimap_uniforms;
+        let rect = minimap.rect(minimap::screen_size(camera));
+        let bounds = minimap.world_bounds;
+        let viewport: Vec<f32> = minimap
+            .viewport(camera)
+            .iter()
+            .flat_map(|(x, y)| [*x, *y])
+            .collect();
+
+        unsafe {
+            gl::UseProgram(self.minimap_program);
+
+            gl::Uniform1f(uniforms.world_height, buffers.world_size.1 as f32);
+            gl::Uniform1f(uniforms.world_width, buffers.world_size.0 as f32);
+            gl::Uniform1f(uniforms.size, HEX_SIZE);
+            gl::Uniform1i(uniforms.lod_levels, buffers.lod_levels);
+            gl::Uniform1iv(
+                uniforms.lod_offsets,
+                buffers.lod_offsets.len() as i32,
+                buffers.lod_offsets.as_ptr(),
+            );
+            gl::Uniform1iv(
+                uniforms.lod_widths,
+                buffers.lod_widths.len() as i32,
+                buffers.lod_widths.as_ptr(),
+            );
+            gl::Uniform4f(uniforms.minimap_rect, rect.0, rect.1, rect.2, rect.3);
+            gl::Uniform4f(
+                uniforms.world_bounds,
+                bounds.0,
+                bounds.1,
+                bounds.2,
+                bounds.3,
+            );
+            gl::Uniform2fv(uniforms.viewport, 4, viewport.as_ptr());
+
+            gl::BindVertexArray(self.vao);
+            gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+
+            gl::BindVertexArray(0);
+            gl::UseProgram(0);
+        }
+    }
+
+    pub fn draw_timing(self: &Self, timer: &FrameTimer) {
+        let uniforms = &self.timing_uniforms;
+        let samples = timer.samples();
+
+        unsafe {
+            gl::UseProgram(self.timing_program);
+
+            gl::Uniform1fv(uniforms.frame_times, FRAME_SAMPLES as i32, samples.as_ptr());
+            gl::Uniform1i(uniforms.frame_count, timer.count() as i32);
+            gl::Uniform4f(
+                uniforms.graph_rect,
+                TIMING_GRAPH_RECT.0,
+                TIMING_GRAPH_RECT.1,
+                TIMING_GRAPH_RECT.2,
+                TIMING_GRAPH_RECT.3,
+            );
+            gl::Uniform1f(uniforms.graph_scale, TIMING_GRAPH_SCALE);
+
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+
+            gl::BindVertexArray(self.vao);
+            gl::DrawElements(gl::TRIANGLES, 6, gl::UNSIGNED_INT, std::ptr::null());
+
+            gl::BindVertexArray(0);
+            gl::Disable(gl::BLEND);
+            gl::UseProgram(0);
+        }
+    }
+
+    pub fn draw_ui(self: &Self, ui: &Ui, screen_size: (f32, f32)) {
+        let quads = ui.quads();
+        if quads.is_empty() {
+            return;
+        }
+
+        let mut vertices = Vec::with_capacity(quads.len() * 6 * UI_VERTEX_FLOATS);
+        for quad in quads {
+            let (x, y, width, height) = quad.rect;
+            let (u0, v0, u1, v1) = quad.uv;
+            let corners = [
+                (x, y, u0, v0),
+                (x + width, y, u1, v0),
+                (x + width, y + height, u1, v1),
+                (x + width, y + height, u1, v1),
+                (x, y + height, u0, v1),
+                (x, y, u0, v0),
+            ];
+            for (px, py, u, v) in corners {
+                vertices.extend_from_slice(&[px, py, u, v]);
+                vertices.extend_from_slice(&quad.color);
+            }
+        }
+
+        unsafe {
+            gl::UseProgram(self.ui_program);
+            gl::Uniform2f(self.ui_uniforms.screen_size, screen_size.0, screen_size.1);
+
+            gl::ActiveTexture(gl::TEXTURE0);
+            gl::BindTexture(gl::TEXTURE_2D, self.font_texture);
+
+            gl::BindVertexArray(self.ui_vao);
+            gl::BindBuffer(gl::ARRAY_BUFFER, self.ui_vbo);
+            gl::BufferData(
+                gl::ARRAY_BUFFER,
+                (vertices.len() * std::mem::size_of::<f32>()) as isize,
+                vertices.as_ptr() as *const _,
+                gl::STREAM_DRAW,
+            );
+
+            gl::Enable(gl::BLEND);
+            gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+
+            gl::DrawArrays(gl::TRIANGLES, 0, (vertices.len() / UI_VERTEX_FLOATS) as i32);
+
+            gl::Disable(gl::BLEND);
+            gl::BindVertexArray(0);
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+            gl::UseProgram(0);
+        }
+    }
+}
+
+impl Drop for Renderer {
+    fn drop(self: &mut Self) {
+        unsafe {
+            gl::DeleteProgram(self.program);
+            gl::DeleteProgram(self.timing_program);
+            gl::DeleteProgram(self.minimap_program);
+            gl::DeleteProgram(self.ui_program);
+            gl::DeleteVertexArrays(1, &self.vao);
+            gl::DeleteBuffers(1, &self.vbo);
+            gl::DeleteBuffers(1, &self.ebo);
+            gl::DeleteVertexArrays(1, &self.ui_vao);
+            gl::DeleteBuffers(1, &self.ui_vbo);
+            gl::DeleteTextures(1, &self.font_texture);
+        }
+    }
+}
@@ -0,0 +1,458 @@
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use crate::{
+    Cell, CellType, World, WorldGenConfig,
+    config::{BiomeThresholds, NoiseLayer},
+    get_world_size,
+    layers::{LayerData, LayerKind, Layers},
+};
+
+const MAGIC: &[u8; 4] = b"CIVW";
+const VERSION: u32 = 1;
+// Longest layer name in bytes, so a corrupt length cannot allocate the whole file.
+const MAX_LAYER_NAME: usize = 256;
+
+fn read_u32(reader: &mut impl Read) -> Result<u32, String> {
+    let mut buffer = [0; 4];
+    reader.read_exact(&mut buffer).map_err(|e| e.to_string())?;
+    Ok(u32::from_le_bytes(buffer))
+}
+
+fn read_f64(reader: &mut impl Read) -> Result<f64, String> {
+    let mut buffer = [0; 8];
+    reader.read_exact(&mut buffer).map_err(|e| e.to_string())?;
+    Ok(f64::from_le_bytes(buffer))
+}
+
+fn read_u8(reader: &mut impl Read) -> Result<u8, String> {
+    let mut buffer = [0; 1];
+    reader.read_exact(&mut buffer).map_err(|e| e.to_string())?;
+    Ok(buffer[0])
+}
+
+fn read_f32(reader: &mut impl Read) -> Result<f32, String> {
+    let mut buffer = [0; 4];
+    reader.read_exact(&mut buffer).map_err(|e| e.to_string())?;
+    Ok(f32::from_le_bytes(buffer))
+}
+
+fn write_noise_layer(layer: &NoiseLayer, data: &mut Vec<u8>) {
+    data.extend_from_slice(&(layer.octaves as u32).to_le_bytes());
+    for value in [layer.frequency, layer.lacunarity, layer.persistence] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+}
+
+fn read_noise_layer(reader: &mut impl Read) -> Result<NoiseLayer, String> {
+    Ok(NoiseLayer {
+        octaves: read_u32(reader)? as usize,
+        frequency: read_f64(reader)?,
+        lacunarity: read_f64(reader)?,
+        persistence: read_f64(reader)?,
+    })
+}
+
+fn write_config(config: &WorldGenConfig, writer: &mut impl Write) -> Result<(), String> {
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(&config.scale.to_le_bytes());
+    for layer in [
+        &config.altitude,
+        &config.temperature,
+        &config.vegetation,
+        &config.beach,
+        &config.cliff,
+        &config.lake,
+    ] {
+        write_noise_layer(layer, &mut data);
+    }
+    data.extend_from_slice(&(config.vegetation_roughness as u32).to_le_bytes());
+
+    let t = &config.thresholds;
+    for value in [
+        config.vegetation_power,
+        config.cliff_attenuation,
+        config.cliff_exponent,
+        config.river_chance,
+        config.river_climb,
+        t.snow,
+        t.high_mountain,
+        t.medium_mountain,
+        t.mountain,
+        t.cliff_altitude,
+        t.medium_cliff_bias,
+        t.cliff_bias,
+        t.land,
+        t.sand_temperature,
+        t.dirt_temperature,
+        t.grass_temperature,
+        t.tree_vegetation,
+        t.tundra_temperature,
+        t.beach_altitude,
+        t.beach_bias,
+        t.beach_temperature,
+        t.shallow_water,
+        t.ice_temperature,
+        t.water,
+        t.medium_water,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    writer.write_all(&data).map_err(|e| e.to_string())
+}
+
+// Read in the order `write_config` writes.
+fn read_config(reader: &mut impl Read) -> Result<WorldGenConfig, String> {
+    let scale = read_f64(reader)?;
+    let altitude = read_noise_layer(reader)?;
+    let temperature = read_noise_layer(reader)?;
+    let vegetation = read_noise_layer(reader)?;
+    let beach = read_noise_layer(reader)?;
+    let cliff = read_noise_layer(reader)?;
+    let lake = read_noise_layer(reader)?;
+    let vegetation_roughness = read_u32(reader)? as usize;
+
+    Ok(WorldGenConfig {
+        scale,
+        altitude,
+        temperature,
+        vegetation,
+        vegetation_roughness,
+        vegetation_power: read_f64(reader)?,
+        beach,
+        cliff,
+        cliff_attenuation: read_f64(reader)?,
+        cliff_exponent: read_f64(reader)?,
+        lake,
+        river_chance: read_f64(reader)?,
+        river_climb: read_f64(reader)?,
+        thresholds: BiomeThresholds {
+            snow: read_f64(reader)?,
+            high_mountain: read_f64(reader)?,
+            medium_mountain: read_f64(reader)?,
+            mountain: read_f64(reader)?,
+            cliff_altitude: read_f64(reader)?,
+            medium_cliff_bias: read_f64(reader)?,
+            cliff_bias: read_f64(reader)?,
+            land: read_f64(reader)?,
+            sand_temperature: read_f64(reader)?,
+            dirt_temperature: read_f64(reader)?,
+            grass_temperature: read_f64(reader)?,
+            tree_vegetation: read_f64(reader)?,
+            tundra_temperature: read_f64(reader)?,
+            beach_altitude: read_f64(reader)?,
+            beach_bias: read_f64(reader)?,
+            beach_temperature: read_f64(reader)?,
+            shallow_water: read_f64(reader)?,
+            ice_temperature: read_f64(reader)?,
+            water: read_f64(reader)?,
+            medium_water: read_f64(reader)?,
+        },
+    })
+}
+
+// Each layer is its name, kind and one value per cell.
+fn write_layers(layers: &Layers, writer: &mut impl Write) -> Result<(), String> {
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(&(layers.len() as u32).to_le_bytes());
+    for layer in layers.iter() {
+        data.extend_from_slice(&(layer.name.len() as u32).to_le_bytes());
+        data.extend_from_slice(layer.name.as_bytes());
+        data.push(layer.data.kind() as u8);
+        match &layer.data {
+            LayerData::Scalar(values) => {
+                for value in values {
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+            LayerData::Category(values) => data.extend_from_slice(values),
+            LayerData::Owner(values) => {
+                for value in values {
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+        }
+    }
+    writer.write_all(&data).map_err(|e| e.to_string())
+}
+
+fn read_layers(
+    reader: &mut impl Read,
+    world_width: u32,
+    world_height: u32,
+    path: &Path,
+) -> Result<Layers, String> {
+    let mut layers = Layers::new(world_width as i32, world_height as i32);
+    let len = world_width as usize * world_height as usize;
+
+    for _ in 0..read_u32(reader)? {
+        let name_len = read_u32(reader)? as usize;
+        if name_len > MAX_LAYER_NAME {
+            return Err(format!(
+                "{}: layer name of {} bytes is longer than {}",
+                path.display(),
+                name_len,
+                MAX_LAYER_NAME
+            ));
+        }
+        let mut name = vec![0; name_len];
+        reader.read_exact(&mut name).map_err(|e| e.to_string())?;
+        let name = String::from_utf8(name)
+            .map_err(|_| format!("{}: layer name is not UTF-8", path.display()))?;
+
+        let kind = read_u8(reader)?;
+        let data = match LayerKind::from_u8(kind) {
+            Some(LayerKind::Scalar) => LayerData::Scalar(
+                (0..len)
+                    .map(|_| read_f32(reader))
+                    .collect::<Result<_, _>>()?,
+            ),
+            Some(LayerKind::Category) => {
+                let mut values = vec![0; len];
+                reader.read_exact(&mut values).map_err(|e| e.to_string())?;
+                LayerData::Category(values)
+            }
+            Some(LayerKind::Owner) => LayerData::Owner(
+                (0..len)
+                    .map(|_| read_u32(reader))
+                    .collect::<Result<_, _>>()?,
+            ),
+            None => {
+                return Err(format!(
+                    "{}: unknown kind {} of layer {}",
+                    path.display(),
+                    kind,
+                    name
+                ));
+            }
+        };
+        layers.insert(&name, data)?;
+    }
+
+    Ok(layers)
+}
+
+// Writes next to `path` and renames over it at the end, so a failed save never leaves a
+// truncated file where a good one used to be.
+pub fn save_world(world: &World, path: &Path) -> Result<(), String> {
+    for layer in world.layers.iter() {
+        if layer.name.len() > MAX_LAYER_NAME {
+            return Err(format!(
+                "layer name {} is longer than {} bytes",
+                layer.name, MAX_LAYER_NAME
+            ));
+        }
+    }
+
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    let saved = write_world(world, &temp_path)
+        .and_then(|()| fs::rename(&temp_path, path).map_err(|e| e.to_string()));
+    if saved.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+    saved.map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn write_world(world: &World, path: &Path) -> Result<(), String> {
+    let file = File::create(path).map_err(|e| e.to_string())?;
+    let mut writer = BufWriter::new(file);
+
+    let (world_width, world_height) = get_world_size(&world.map);
+
+    let mut header: Vec<u8> = vec![];
+    header.extend_from_slice(MAGIC);
+    header.extend_from_slice(&VERSION.to_le_bytes());
+    header.extend_from_slice(&(world_width as u32).to_le_bytes());
+    header.extend_from_slice(&(world_height as u32).to_le_bytes());
+    writer.write_all(&header).map_err(|e| e.to_string())?;
+
+    for row in &world.map {
+        for cell in row {
+            let mut data: Vec<u8> = vec![cell.cell_type as u8];
+            data.extend_from_slice(&cell.altitude.to_le_bytes());
+            data.extend_from_slice(&cell.relative_altitude.to_le_bytes());
+            data.extend_from_slice(&cell.temperature.to_le_bytes());
+            data.extend_from_slice(&cell.vegetation.to_le_bytes());
+            data.extend_from_slice(&cell.beach_bias.to_le_bytes());
+            data.extend_from_slice(&cell.cliff_bias.to_le_bytes());
+            data.extend_from_slice(&cell.lake_bias.to_le_bytes());
+            writer.write_all(&data).map_err(|e| e.to_string())?;
+        }
+    }
+
+    let mut data: Vec<u8> = vec![];
+    data.extend_from_slice(&(world.rivers.len() as u32).to_le_bytes());
+    for river in &world.rivers {
+        data.extend_from_slice(&(river.len() as u32).to_le_bytes());
+        for (x, y) in river {
+            data.extend_from_slice(&(*x as u32).to_le_bytes());
+            data.extend_from_slice(&(*y as u32).to_le_bytes());
+        }
+    }
+    writer.write_all(&data).map_err(|e| e.to_string())?;
+
+    write_layers(&world.layers, &mut writer)?;
+    write_config(&world.config, &mut writer)?;
+
+    writer.flush().map_err(|e| e.to_string())
+}
+
+pub fn load_world(path: &Path) -> Result<World, String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut reader = BufReader::new(file);
+
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic).map_err(|e| e.to_string())?;
+    if &magic != MAGIC {
+        return Err(format!("{}: not a world file", path.display()));
+    }
+
+    let version = read_u32(&mut reader)?;
+    if version != VERSION {
+        return Err(format!(
+            "{}: unsupported world file version {}",
+            path.display(),
+            version
+        ));
+    }
+
+    let world_width = read_u32(&mut reader)?;
+    let world_height = read_u32(&mut reader)?;
+    if world_width == 0 || world_height == 0 {
+        return Err(format!("{}: world has no cells", path.display()));
+    }
+    // Sizes are `i32` everywhere else.
+    if world_width > i32::MAX as u32 / 2 || world_height > i32::MAX as u32 {
+        return Err(format!(
+            "{}: world of {}x{} cells is too large",
+            path.display(),
+            world_width,
+            world_height
+        ));
+    }
+
+    let mut world: Vec<Vec<Cell>> = vec![];
+
+    for _ in 0..world_height {
+        let mut row = Vec::new();
+        for _ in 0..world_width {
+            let mut cell_type = [0; 1];
+            reader
+                .read_exact(&mut cell_type)
+                .map_err(|e| e.to_string())?;
+            let cell_type = CellType::from_u8(cell_type[0])
+                .ok_or_else(|| format!("{}: unknown cell type {}", path.display(), cell_type[0]))?;
+
+            let altitude = read_f64(&mut reader)?;
+            let relative_altitude = read_f64(&mut reader)?;
+            let temperature = read_f64(&mut reader)?;
+            let vegetation = read_f64(&mut reader)?;
+            let beach_bias = read_f64(&mut reader)?;
+            let cliff_bias = read_f64(&mut reader)?;
+            let lake_bias = read_f64(&mut reader)?;
+
+            row.push(Cell {
+                cell_type,
+                altitude,
+                relative_altitude,
+                temperature,
+                vegetation,
+                beach_bias,
+                cliff_bias,
+                lake_bias,
+            });
+        }
+        world.push(row);
+    }
+
+    let mut rivers = vec![];
+    for _ in 0..read_u32(&mut reader)? {
+        let mut river = vec![];
+        for _ in 0..read_u32(&mut reader)? {
+            let (x, y) = (read_u32(&mut reader)?, read_u32(&mut reader)?);
+            // River points are doublewidth hex coordinates.
+            if x >= world_width * 2 || y >= world_height {
+                return Err(format!(
+                    "{}: river point {},{} is outside the world",
+                    path.display(),
+                    x,
+                    y
+                ));
+            }
+            river.push((x as usize, y as usize));
+        }
+        rivers.push(river);
+    }
+
+    let layers = read_layers(&mut reader, world_width, world_height, path)?;
+    let config = read_config(&mut reader)?;
+
+    Ok(World {
+        map: world,
+        rivers,
+        layers,
+        config,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generate_world;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("civilizations-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn round_trips_a_world() {
+        let mut config = WorldGenConfig::new();
+        config.thresholds.land = 0.62;
+        let world = crate::generate_world_with_config(5, 24, 16, &config);
+        let path = temp_path("round-trip.civw");
+        save_world(&world, &path).unwrap();
+        let loaded = load_world(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(loaded.map == world.map);
+        assert_eq!(loaded.rivers, world.rivers);
+        assert_eq!(loaded.layers.len(), world.layers.len());
+        assert_eq!(loaded.config, world.config);
+    }
+
+    #[test]
+    fn rejects_rivers_outside_the_world() {
+        let mut world = generate_world(5, 24, 16);
+        world.rivers.push(vec![(48, 0)]);
+        let path = temp_path("river.civw");
+        save_world(&world, &path).unwrap();
+        let loaded = load_world(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(loaded.is_err());
+    }
+
+    #[test]
+    fn rejects_long_layer_names() {
+        let mut world = generate_world(5, 24, 16);
+        let name = "x".repeat(MAX_LAYER_NAME + 1);
+        world.layers.register(&name, LayerKind::Scalar).unwrap();
+
+        // A failed save leaves the world that was there before.
+        let path = temp_path("layer.civw");
+        save_world(&generate_world(5, 24, 16), &path).unwrap();
+        let before = std::fs::read(&path).unwrap();
+        let saved = save_world(&world, &path);
+        let after = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(saved.is_err());
+        assert_eq!(before, after);
+    }
+}
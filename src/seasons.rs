@@ -0,0 +1,156 @@
+use std::f64::consts::PI;
+
+use crate::{Cell, CellType, Climate, World, layers::LayerData};
+
+pub const YIELD_LAYER: &str = "yield";
+
+// Temperature below which nothing grows and above which growth is at its best.
+const FROST_TEMPERATURE: f64 = 0.25;
+const GROWTH_TEMPERATURE: f64 = 0.55;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+// Food a cell gives in the best growing conditions.
+pub fn base_yield(cell_type: CellType) -> f64 {
+    match cell_type {
+        CellType::Grass => 2.0,
+        CellType::Dirt => 1.0,
+        CellType::Tree => 1.5,
+        CellType::River => 3.0,
+        CellType::Water | CellType::ShallowWater | CellType::Lake => 1.0,
+        CellType::MediumWater => 0.5,
+        CellType::Tundra => 0.5,
+        CellType::Sand => 0.25,
+        CellType::DeepWater
+        | CellType::Snow
+        | CellType::Mountain
+        | CellType::MediumMountain
+        | CellType::HighMountain
+        | CellType::Ice
+        | CellType::Cliff
+        | CellType::MediumCliff => 0.0,
+    }
+}
+
+// The yield of a cell at `temperature`; swollen rivers water their cells.
+pub fn cell_yield(cell: &Cell, temperature: f64, river_swell: f64) -> f64 {
+    let growth = ((temperature - FROST_TEMPERATURE) / (GROWTH_TEMPERATURE - FROST_TEMPERATURE))
+        .clamp(0.0, 1.0);
+    let watered = if cell.cell_type == CellType::River {
+        1.0 + river_swell
+    } else {
+        1.0
+    };
+    base_yield(cell.cell_type) * growth * watered
+}
+
+// Stores the yield of every cell under `climate` in the world's yield layer.
+pub fn update_yields(world: &mut World, climate: &Climate) {
+    let world_height = world.map.len() as i32;
+    let yields: Vec<f32> = world
+        .map
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            let offset = climate.temperature_offset_at(y, world_height);
+            row.iter().map(move |cell| {
+                cell_yield(cell, cell.temperature + offset, climate.river_swell) as f32
+            })
+        })
+        .collect();
+
+    world
+        .layers
+        .insert(YIELD_LAYER, LayerData::Scalar(yields))
+        .unwrap();
+}
+
+// A year of days that warms the top half of the world while it cools the bottom half. Day 0
+// is the start of spring in the top half.
+pub struct SeasonCycle {
+    pub enabled: bool,
+    pub day: f64,
+    pub days_per_year: f64,
+    pub days_per_second: f64,
+    // Temperature swing at the top and bottom rows.
+    pub amplitude: f64,
+}
+
+impl Default for SeasonCycle {
+    fn default() -> SeasonCycle {
+        SeasonCycle::new()
+    }
+}
+
+impl SeasonCycle {
+    pub fn new() -> SeasonCycle {
+        SeasonCycle {
+            enabled: false,
+            day: 0.0,
+            days_per_year: 360.0,
+            days_per_second: 10.0,
+            amplitude: 0.15,
+        }
+    }
+
+    // Advances the clock and returns whether a new day started, which is when the world is
+    // reclassified.
+    pub fn advance(self: &mut Self, dt: f64) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let previous = self.day.floor();
+        self.day = (self.day + dt * self.days_per_second).rem_euclid(self.days_per_year);
+        self.day.floor() != previous
+    }
+
+    // Steps to the start of the next day without a clock, for headless simulation.
+    pub fn next_day(self: &mut Self) {
+        self.day = (self.day.floor() + 1.0).rem_euclid(self.days_per_year);
+    }
+
+    // Position in the year in radians.
+    pub fn phase(self: &Self) -> f64 {
+        2.0 * PI * self.day.floor() / self.days_per_year
+    }
+
+    // Season of the top half of the world.
+    pub fn season(self: &Self) -> Season {
+        match (self.day / self.days_per_year * 4.0) as usize {
+            0 => Season::Spring,
+            1 => Season::Summer,
+            2 => Season::Autumn,
+            _ => Season::Winter,
+        }
+    }
+
+    // `base` with this day's seasonal temperature and river swell, which peaks with the
+    // snowmelt in the middle of spring.
+    pub fn climate(self: &Self, base: &Climate) -> Climate {
+        if !self.enabled {
+            return *base;
+        }
+
+        let phase = self.phase();
+        Climate {
+            seasonal_temperature: base.seasonal_temperature + self.amplitude * phase.sin(),
+            river_swell: (base.river_swell + (phase + PI / 4.0).sin().max(0.0)).min(1.0),
+            ..*base
+        }
+    }
+
+    pub fn describe(self: &Self) -> String {
+        format!(
+            "{:?}, day {}/{}",
+            self.season(),
+            self.day.floor() as i32 + 1,
+            self.days_per_year as i32
+        )
+    }
+}
@@ -0,0 +1,74 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::renderer::ShaderSources;
+
+const SHADER_FILES: [&str; 6] = [
+    "vertex.glsl",
+    "fragment.glsl",
+    "timing.glsl",
+    "minimap.glsl",
+    "ui_vertex.glsl",
+    "ui.glsl",
+];
+const CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+pub struct ShaderWatcher {
+    dir: PathBuf,
+    modified: [Option<SystemTime>; 6],
+    last_check: Instant,
+}
+
+impl ShaderWatcher {
+    pub fn new(dir: PathBuf) -> ShaderWatcher {
+        let mut watcher = ShaderWatcher {
+            dir,
+            modified: [None; 6],
+            last_check: Instant::now(),
+        };
+        watcher.modified = watcher.modified_times();
+        watcher
+    }
+
+    fn modified_times(self: &Self) -> [Option<SystemTime>; 6] {
+        SHADER_FILES.map(|file| {
+            fs::metadata(self.dir.join(file))
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        })
+    }
+
+    pub fn load(self: &Self) -> Result<ShaderSources, String> {
+        let read = |file: &str| {
+            let path = self.dir.join(file);
+            fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))
+        };
+        let [vertex, fragment, timing, minimap, ui_vertex, ui] = SHADER_FILES;
+
+        Ok(ShaderSources {
+            vertex: read(vertex)?,
+            fragment: read(fragment)?,
+            timing: read(timing)?,
+            minimap: read(minimap)?,
+            ui_vertex: read(ui_vertex)?,
+            ui: read(ui)?,
+        })
+    }
+
+    pub fn poll(self: &mut Self) -> bool {
+        if self.last_check.elapsed() < CHECK_INTERVAL {
+            return false;
+        }
+        self.last_check = Instant::now();
+
+        let modified = self.modified_times();
+        if modified == self.modified {
+            return false;
+        }
+        self.modified = modified;
+        true
+    }
+}
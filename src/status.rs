@@ -0,0 +1,29 @@
+const MESSAGE_DURATION: f64 = 3.0;
+
+// A one-line message shown in the window title for a few seconds.
+pub struct StatusMessage {
+    message: Option<(String, f64)>,
+}
+
+impl StatusMessage {
+    pub fn new() -> StatusMessage {
+        StatusMessage { message: None }
+    }
+
+    pub fn show(self: &mut Self, message: String) {
+        self.message = Some((message, MESSAGE_DURATION));
+    }
+
+    pub fn update(self: &mut Self, dt: f64) {
+        if let Some((_, remaining)) = &mut self.message {
+            *remaining -= dt;
+            if *remaining <= 0.0 {
+                self.message = None;
+            }
+        }
+    }
+
+    pub fn text(self: &Self) -> Option<&str> {
+        self.message.as_ref().map(|(message, _)| message.as_str())
+    }
+}
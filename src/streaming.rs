@@ -0,0 +1,113 @@
+use civilizations::{
+    Camera2D, Cell, DirtyRegions, HEX_SIZE, Layers, World, WorldGenConfig,
+    chunks::{CHUNK_SIZE, ChunkStore, ChunkWindow},
+    hex,
+};
+
+use crate::{inspector::Inspector, navigation::Navigation};
+
+// Chunks across the window of the world the viewer draws.
+const WINDOW_CHUNKS: i32 = 12;
+// The window follows the camera once the camera is this many chunks from its center.
+const RECENTER_DISTANCE: i32 = 3;
+const CHUNK_BUDGET: usize = 128 * 1024 * 1024;
+
+// Unbounded world made of chunks generated around the camera. The viewer draws a window of
+// chunks as a regular map and the window moves along when the camera gets near its edge.
+pub struct Streaming {
+    store: ChunkStore,
+    window: ChunkWindow,
+}
+
+impl Streaming {
+    pub fn new(seed: u64, config: &WorldGenConfig) -> Streaming {
+        Streaming {
+            store: ChunkStore::new(seed, config, CHUNK_BUDGET),
+            window: ChunkWindow::new(WINDOW_CHUNKS, (0, 0)),
+        }
+    }
+
+    // Placeholder cells until the chunks arrive; rivers and layers stay inside their chunks.
+    pub fn world(self: &Self) -> World {
+        let (width, height) = self.window.world_size();
+
+        World {
+            map: self.window.placeholder_map(),
+            rivers: vec![],
+            layers: Layers::new(width, height),
+            config: *self.store.config(),
+        }
+    }
+
+    pub fn reset(
+        self: &mut Self,
+        seed: u64,
+        config: &WorldGenConfig,
+        map: &mut Vec<Vec<Cell>>,
+        dirty: &mut DirtyRegions,
+    ) {
+        self.store.reset(seed, config);
+        self.window.fill(map, &self.store, dirty);
+    }
+
+    // Moves the window with the camera, requests its chunks and copies finished ones into
+    // the map.
+    pub fn update(
+        self: &mut Self,
+        map: &mut Vec<Vec<Cell>>,
+        camera: &mut Camera2D,
+        navigation: &mut Navigation,
+        inspector: &mut Inspector,
+        dirty: &mut DirtyRegions,
+    ) {
+        let (column, row) = hex::pixel_to_doublewidth(
+            (camera.position.0 as f32, camera.position.1 as f32),
+            HEX_SIZE,
+        );
+        let camera_chunk = self
+            .window
+            .chunk_of((column / 2.0).floor() as i32, row as i32);
+        let center = self.window.center();
+
+        if (camera_chunk.0 - center.0)
+            .abs()
+            .max((camera_chunk.1 - center.1).abs())
+            >= RECENTER_DISTANCE
+        {
+            let (dx, dy) = self.window.move_to(camera_chunk);
+            let cells = (dx * CHUNK_SIZE as i32, dy * CHUNK_SIZE as i32);
+
+            // Chunks have an even height, so rows keep their offset and a whole number of
+            // cells maps to the same pixel offset everywhere.
+            let (px, py) =
+                hex::doublewidth_to_pixel(((cells.0 * 2) as f32, cells.1 as f32), HEX_SIZE);
+            let offset = (px as f64, py as f64);
+            camera.position = (camera.position.0 - offset.0, camera.position.1 - offset.1);
+            navigation.shift(offset);
+            inspector.shift(cells.0, cells.1, self.window.world_size());
+
+            self.window.fill(map, &self.store, dirty);
+        }
+
+        for coord in self.window.coords() {
+            self.store.request(coord);
+        }
+        for coord in self.store.poll() {
+            if self.window.contains(coord) {
+                let chunk = self.store.get(coord).unwrap();
+                self.window.copy_chunk(map, coord, chunk, dirty);
+            }
+        }
+        self.store.evict(self.window.center());
+    }
+
+    pub fn describe(self: &Self) -> String {
+        format!(
+            "chunks {} loaded ({:.0} MiB), {} pending, window at {:?}",
+            self.store.loaded(),
+            self.store.memory() as f64 / (1024.0 * 1024.0),
+            self.store.pending(),
+            self.window.origin
+        )
+    }
+}
@@ -0,0 +1,380 @@
+use std::collections::HashSet;
+
+use sdl3::{event::Event, mouse::MouseButton};
+
+use crate::font::{self, GLYPH_HEIGHT, GLYPH_WIDTH};
+
+const TEXT_SCALE: f32 = 2.0;
+const CHAR_WIDTH: f32 = (GLYPH_WIDTH + 1) as f32 * TEXT_SCALE;
+const LINE_HEIGHT: f32 = (GLYPH_HEIGHT + 4) as f32 * TEXT_SCALE;
+const PADDING: f32 = 8.0;
+const SPACING: f32 = 4.0;
+const SLIDER_HEIGHT: f32 = 8.0;
+
+const PANEL_COLOR: [f32; 4] = [0.08, 0.09, 0.11, 0.85];
+const TITLE_COLOR: [f32; 4] = [0.16, 0.18, 0.24, 0.95];
+const TEXT_COLOR: [f32; 4] = [0.92, 0.92, 0.92, 1.0];
+const WIDGET_COLOR: [f32; 4] = [0.24, 0.26, 0.3, 1.0];
+const HOT_COLOR: [f32; 4] = [0.32, 0.35, 0.42, 1.0];
+const ACTIVE_COLOR: [f32; 4] = [0.42, 0.48, 0.62, 1.0];
+const ACCENT_COLOR: [f32; 4] = [0.4, 0.65, 0.95, 1.0];
+
+pub type Rect = (f32, f32, f32, f32);
+
+#[derive(Clone, Copy)]
+pub struct Quad {
+    pub rect: Rect,
+    pub uv: (f32, f32, f32, f32),
+    pub color: [f32; 4],
+}
+
+struct Panel {
+    background: usize,
+    rect: Rect,
+    cursor_y: f32,
+}
+
+fn contains(rect: Rect, (x, y): (f32, f32)) -> bool {
+    x >= rect.0 && y >= rect.1 && x < rect.0 + rect.2 && y < rect.1 + rect.3
+}
+
+// Breaks text on spaces so that no line is longer than `max_chars`, unless a single word is.
+fn wrap(text: &str, max_chars: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            if !line.is_empty() && line.len() + 1 + word.len() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line += word;
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+// Immediate-mode widgets: panels are rebuilt every frame and hit testing uses the previous frame's panels.
+pub struct Ui {
+    mouse: (f32, f32),
+    pressed: bool,
+    released: bool,
+    active: Option<usize>,
+    next_id: usize,
+    panels: Vec<Rect>,
+    frame_panels: Vec<Rect>,
+    panel: Option<Panel>,
+    open_sections: HashSet<String>,
+    quads: Vec<Quad>,
+}
+
+impl Ui {
+    pub fn new() -> Ui {
+        Ui {
+            mouse: (0.0, 0.0),
+            pressed: false,
+            released: false,
+            active: None,
+            next_id: 0,
+            panels: Vec::new(),
+            frame_panels: Vec::new(),
+            panel: None,
+            open_sections: HashSet::new(),
+            quads: Vec::new(),
+        }
+    }
+
+    pub fn wants_mouse(self: &Self, x: f32, y: f32) -> bool {
+        self.active.is_some() || self.panels.iter().any(|panel| contains(*panel, (x, y)))
+    }
+
+    // Returns true when the event is used by the UI and should not reach the map.
+    pub fn handle_event(self: &mut Self, event: &Event) -> bool {
+        match *event {
+            Event::MouseMotion { x, y, .. } => {
+                self.mouse = (x, y);
+                self.active.is_some()
+            }
+            Event::MouseButtonDown {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                self.mouse = (x, y);
+                if self.wants_mouse(x, y) {
+                    self.pressed = true;
+                    true
+                } else {
+                    false
+                }
+            }
+            Event::MouseButtonUp {
+                mouse_btn: MouseButton::Left,
+                x,
+                y,
+                ..
+            } => {
+                self.mouse = (x, y);
+                if self.active.is_some() || self.pressed {
+                    self.released = true;
+                    true
+                } else {
+                    false
+                }
+            }
+            Event::MouseButtonDown { x, y, .. }
+            | Event::MouseButtonUp { x, y, .. }
+            | Event::MouseWheel {
+                mouse_x: x,
+                mouse_y: y,
+                ..
+            } => self.wants_mouse(x, y),
+            _ => false,
+        }
+    }
+
+    pub fn begin_frame(self: &mut Self) {
+        self.quads.clear();
+        self.frame_panels.clear();
+        self.next_id = 0;
+    }
+
+    pub fn end_frame(self: &mut Self) {
+        std::mem::swap(&mut self.panels, &mut self.frame_panels);
+        self.pressed = false;
+        if self.released {
+            self.active = None;
+            self.released = false;
+        }
+    }
+
+    pub fn quads(self: &Self) -> &[Quad] {
+        &self.quads
+    }
+
+    pub fn rect(self: &mut Self, rect: Rect, color: [f32; 4]) {
+        self.quads.push(Quad {
+            rect,
+            uv: font::solid_uv(),
+            color,
+        });
+    }
+
+    pub fn text(self: &mut Self, text: &str, x: f32, y: f32, color: [f32; 4]) {
+        let top = y + (LINE_HEIGHT - GLYPH_HEIGHT as f32 * TEXT_SCALE) / 2.0;
+        for (i, c) in text.chars().enumerate() {
+            if c == ' ' {
+                continue;
+            }
+            self.quads.push(Quad {
+                rect: (
+                    x + i as f32 * CHAR_WIDTH,
+                    top,
+                    GLYPH_WIDTH as f32 * TEXT_SCALE,
+                    GLYPH_HEIGHT as f32 * TEXT_SCALE,
+                ),
+                uv: font::glyph_uv(c),
+                color,
+            });
+        }
+    }
+
+    // Claims a widget id and updates the active widget from this frame's clicks.
+    fn interact(self: &mut Self, rect: Rect) -> (bool, bool) {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let hovered = contains(rect, self.mouse) && self.active.is_none_or(|active| active == id);
+        if self.pressed && hovered {
+            self.active = Some(id);
+        }
+        (hovered, self.active == Some(id))
+    }
+
+    fn widget_color(hovered: bool, held: bool) -> [f32; 4] {
+        match (hovered, held) {
+            (_, true) => ACTIVE_COLOR,
+            (true, false) => HOT_COLOR,
+            (false, false) => WIDGET_COLOR,
+        }
+    }
+
+    // A screen area outside any panel that reports the mouse position while it is dragged.
+    pub fn drag_area(self: &mut Self, rect: Rect) -> Option<(f32, f32)> {
+        self.frame_panels.push(rect);
+        let (_, held) = self.interact(rect);
+        held.then_some(self.mouse)
+    }
+
+    pub fn begin_panel(self: &mut Self, title: &str, x: f32, y: f32, width: f32) {
+        let background = self.quads.len();
+        self.rect((x, y, width, 0.0), PANEL_COLOR);
+        self.rect((x, y, width, LINE_HEIGHT + SPACING), TITLE_COLOR);
+        self.text(title, x + PADDING, y + SPACING / 2.0, TEXT_COLOR);
+
+        self.panel = Some(Panel {
+            background,
+            rect: (x, y, width, 0.0),
+            cursor_y: y + LINE_HEIGHT + SPACING + PADDING,
+        });
+    }
+
+    // Returns the panel rectangle so the next panel can be placed below it.
+    pub fn end_panel(self: &mut Self) -> Rect {
+        let panel = self.panel.take().expect("end_panel without begin_panel");
+        let (x, y, width, _) = panel.rect;
+        let rect = (x, y, width, panel.cursor_y - SPACING + PADDING - y);
+
+        self.quads[panel.background].rect = rect;
+        self.frame_panels.push(rect);
+        rect
+    }
+
+    fn next_rect(self: &mut Self, height: f32) -> Rect {
+        let panel = self.panel.as_mut().expect("widget outside of a panel");
+        let (x, _, width, _) = panel.rect;
+        let rect = (x + PADDING, panel.cursor_y, width - 2.0 * PADDING, height);
+        panel.cursor_y += height + SPACING;
+        rect
+    }
+
+    fn max_chars(self: &Self) -> usize {
+        let panel = self.panel.as_ref().expect("widget outside of a panel");
+        ((panel.rect.2 - 2.0 * PADDING) / CHAR_WIDTH) as usize
+    }
+
+    pub fn label(self: &mut Self, text: &str) {
+        self.colored_label(text, TEXT_COLOR);
+    }
+
+    pub fn colored_label(self: &mut Self, text: &str, color: [f32; 4]) {
+        for line in wrap(text, self.max_chars()) {
+            let (x, y, _, _) = self.next_rect(LINE_HEIGHT);
+            self.text(&line, x, y, color);
+        }
+    }
+
+    pub fn button(self: &mut Self, text: &str) -> bool {
+        let rect = self.next_rect(LINE_HEIGHT);
+        let (hovered, held) = self.interact(rect);
+
+        self.rect(rect, Ui::widget_color(hovered, held));
+        self.text(text, rect.0 + PADDING, rect.1, TEXT_COLOR);
+
+        held && hovered && self.released
+    }
+
+    pub fn checkbox(self: &mut Self, text: &str, value: &mut bool) -> bool {
+        let rect = self.next_rect(LINE_HEIGHT);
+        let (hovered, held) = self.interact(rect);
+
+        let box_size = LINE_HEIGHT - SPACING;
+        let box_rect = (rect.0, rect.1 + SPACING / 2.0, box_size, box_size);
+        self.rect(box_rect, Ui::widget_color(hovered, held));
+        if *value {
+            let inset = box_size / 4.0;
+            self.rect(
+                (
+                    box_rect.0 + inset,
+                    box_rect.1 + inset,
+                    box_size - 2.0 * inset,
+                    box_size - 2.0 * inset,
+                ),
+                ACCENT_COLOR,
+            );
+        }
+        self.text(text, rect.0 + box_size + PADDING, rect.1, TEXT_COLOR);
+
+        let clicked = held && hovered && self.released;
+        if clicked {
+            *value = !*value;
+        }
+        clicked
+    }
+
+    // Draws a labelled track filled to `t` and returns the new position while it is dragged.
+    fn slider_track(self: &mut Self, label: &str, t: f64) -> Option<f64> {
+        let label_rect = self.next_rect(LINE_HEIGHT);
+        self.text(label, label_rect.0, label_rect.1, TEXT_COLOR);
+
+        let rect = self.next_rect(SLIDER_HEIGHT + SPACING);
+        let (hovered, held) = self.interact(rect);
+        let t = if held {
+            ((self.mouse.0 - rect.0) / rect.2).clamp(0.0, 1.0) as f64
+        } else {
+            t.clamp(0.0, 1.0)
+        };
+
+        let track = (rect.0, rect.1 + SPACING / 2.0, rect.2, SLIDER_HEIGHT);
+        self.rect(track, Ui::widget_color(hovered, held));
+        self.rect(
+            (track.0, track.1, track.2 * t as f32, track.3),
+            ACCENT_COLOR,
+        );
+
+        held.then_some(t)
+    }
+
+    pub fn slider(self: &mut Self, text: &str, value: &mut f64, min: f64, max: f64) -> bool {
+        // Enough decimals to show a hundredth of the range.
+        let decimals = (2.0 - (max - min).log10().floor()).max(0.0) as usize;
+        let label = format!("{}: {:.*}", text, decimals, value);
+
+        match self.slider_track(&label, (*value - min) / (max - min)) {
+            Some(t) => {
+                let new_value = min + t * (max - min);
+                let changed = new_value != *value;
+                *value = new_value;
+                changed
+            }
+            None => false,
+        }
+    }
+
+    pub fn int_slider(
+        self: &mut Self,
+        text: &str,
+        value: &mut usize,
+        min: usize,
+        max: usize,
+    ) -> bool {
+        let label = format!("{}: {}", text, value);
+        let t = (*value as f64 - min as f64) / (max - min) as f64;
+
+        match self.slider_track(&label, t) {
+            Some(t) => {
+                let new_value = min + (t * (max - min) as f64).round() as usize;
+                let changed = new_value != *value;
+                *value = new_value;
+                changed
+            }
+            None => false,
+        }
+    }
+
+    // A header that shows or hides the widgets after it, returns whether it is open.
+    pub fn collapsing(self: &mut Self, title: &str) -> bool {
+        let rect = self.next_rect(LINE_HEIGHT);
+        let (hovered, held) = self.interact(rect);
+
+        if held && hovered && self.released && !self.open_sections.remove(title) {
+            self.open_sections.insert(String::from(title));
+        }
+        let open = self.open_sections.contains(title);
+
+        self.rect(rect, Ui::widget_color(hovered, held));
+        let marker = if open { '-' } else { '+' };
+        self.text(
+            &format!("{} {}", marker, title),
+            rect.0 + PADDING,
+            rect.1,
+            TEXT_COLOR,
+        );
+
+        open
+    }
+}
@@ -0,0 +1,837 @@
+use noise::{Fbm, MultiFractal, NoiseFn, Perlin, RidgedMulti, Turbulence};
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+use rayon::prelude::*;
+use std::time::SystemTime;
+
+use crate::{
+    config::{BiomeThresholds, Climate, NoiseLayer, WorldGenConfig},
+    dirty::DirtyRegions,
+    layers::{LayerData, Layers, NO_OWNER},
+};
+
+pub const WORLD_WIDTH: i32 = 1000;
+pub const WORLD_HEIGTH: i32 = 1000;
+
+// Layers every generated world starts with.
+pub const RIVER_LAYER: &str = "river";
+pub const RIVER_FLOW_LAYER: &str = "river_flow";
+
+// Altitude above the river a bank cell may have and still be flooded by a fully swollen river.
+const RIVER_SWELL_HEIGHT: f64 = 0.01;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CellType {
+    Grass,
+    Dirt,
+    Tree,
+    River,
+    Water,
+    MediumWater,
+    DeepWater,
+    Sand,
+    Snow,
+    Mountain,
+    MediumMountain,
+    HighMountain,
+    Tundra,
+    ShallowWater,
+    Ice,
+    Cliff,
+    MediumCliff,
+    Lake,
+}
+
+fn increase_color_by_height(color: (f64, f64, f64), height: f64) -> (f64, f64, f64) {
+    (
+        (color.0 / (1.0 - height * 1.4)).max(0.0).min(255.0),
+        (color.1 / (1.0 - height * 1.4)).max(0.0).min(255.0),
+        (color.2 / (1.0 - height * 1.4)).max(0.0).min(255.0),
+    )
+}
+
+fn increase_color_by_height_water(color: (f64, f64, f64), height: f64) -> (f64, f64, f64) {
+    (
+        (color.0 / (1.0 - height * 7.0)).max(0.0).min(255.0),
+        (color.1 / (1.0 - height * 7.0)).max(0.0).min(255.0),
+        (color.2 / (1.0 - height * 7.0)).max(0.0).min(255.0),
+    )
+}
+
+fn decrease_color_by_height(color: (f64, f64, f64), height: f64) -> (f64, f64, f64) {
+    (
+        (color.0 * (1.0 - height.cbrt())).max(0.0).min(255.0),
+        (color.1 * (1.0 - height.cbrt())).max(0.0).min(255.0),
+        (color.2 * (1.0 - height.cbrt())).max(0.0).min(255.0),
+    )
+}
+
+impl CellType {
+    pub const ALL: [CellType; 18] = [
+        CellType::Grass,
+        CellType::Dirt,
+        CellType::Tree,
+        CellType::River,
+        CellType::Water,
+        CellType::MediumWater,
+        CellType::DeepWater,
+        CellType::Sand,
+        CellType::Snow,
+        CellType::Mountain,
+        CellType::MediumMountain,
+        CellType::HighMountain,
+        CellType::Tundra,
+        CellType::ShallowWater,
+        CellType::Ice,
+        CellType::Cliff,
+        CellType::MediumCliff,
+        CellType::Lake,
+    ];
+
+    pub fn from_u8(value: u8) -> Option<CellType> {
+        CellType::ALL.get(value as usize).copied()
+    }
+
+    pub fn is_water(self: &Self) -> bool {
+        matches!(
+            self,
+            CellType::Water
+                | CellType::MediumWater
+                | CellType::DeepWater
+                | CellType::ShallowWater
+                | CellType::Ice
+                | CellType::Lake
+        )
+    }
+
+    // Open water a river flows into and ends at.
+    pub fn ends_river(self: &Self) -> bool {
+        matches!(
+            self,
+            CellType::Lake
+                | CellType::Water
+                | CellType::ShallowWater
+                | CellType::MediumWater
+                | CellType::DeepWater
+        )
+    }
+
+    pub fn get_weight(self: &Self) -> i32 {
+        match self {
+            CellType::Grass => 1,
+            CellType::Water => 5,
+            CellType::Sand => 2,
+            CellType::Snow => 2,
+            CellType::Mountain => 100,
+            CellType::DeepWater => 20,
+            CellType::MediumWater => 10,
+            CellType::River => 6,
+            CellType::Tundra => 2,
+            CellType::MediumMountain => 200,
+            CellType::HighMountain => 300,
+            CellType::Dirt => 1,
+            CellType::ShallowWater => 2,
+            CellType::Tree => 5,
+            CellType::Ice => 3,
+            CellType::Cliff => 5,
+            CellType::MediumCliff => 5,
+            CellType::Lake => 5,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cell {
+    pub cell_type: CellType,
+    pub altitude: f64,
+    pub relative_altitude: f64,
+    pub temperature: f64,
+    pub vegetation: f64,
+    pub beach_bias: f64,
+    pub cliff_bias: f64,
+    pub lake_bias: f64,
+}
+
+impl Cell {
+    pub fn get_color(self: &Self) -> (f64, f64, f64) {
+        match self.cell_type {
+            CellType::Grass => {
+                increase_color_by_height((125.0, 205.0, 127.0), self.relative_altitude)
+            }
+            CellType::ShallowWater => {
+                increase_color_by_height_water((40.0, 100.0, 160.0), self.relative_altitude)
+            }
+            CellType::Water => {
+                increase_color_by_height_water((15.0, 15.0, 160.0), self.relative_altitude)
+            }
+            CellType::MediumWater => {
+                increase_color_by_height_water((22.0, 30.0, 64.0), self.relative_altitude)
+            }
+            CellType::DeepWater => {
+                decrease_color_by_height((30.0, 50.0, 100.0), self.relative_altitude)
+            }
+            CellType::Sand => {
+                increase_color_by_height((230.0, 210.0, 100.0), self.relative_altitude)
+            }
+            CellType::Snow => {
+                increase_color_by_height((230.0, 230.0, 230.0), self.relative_altitude)
+            }
+            CellType::River => {
+                increase_color_by_height((50.0, 100.0, 150.0), self.relative_altitude)
+            }
+            CellType::Tundra => {
+                increase_color_by_height((20.0, 100.0, 20.0), self.relative_altitude)
+            }
+            CellType::Mountain => {
+                decrease_color_by_height((100.0, 100.0, 100.0), self.relative_altitude)
+            }
+            CellType::MediumMountain => {
+                decrease_color_by_height((80.0, 80.0, 80.0), self.relative_altitude)
+            }
+            CellType::HighMountain => {
+                decrease_color_by_height((60.0, 60.0, 60.0), self.relative_altitude)
+            }
+            CellType::Dirt => {
+                increase_color_by_height((196.0, 210.0, 130.0), self.relative_altitude)
+            }
+            CellType::Tree => increase_color_by_height((50.0, 150.0, 50.0), self.relative_altitude),
+            CellType::Ice => {
+                increase_color_by_height_water((150.0, 150.0, 200.0), self.relative_altitude)
+            }
+            CellType::Cliff => {
+                decrease_color_by_height((150.0, 150.0, 130.0), self.relative_altitude)
+            }
+            CellType::MediumCliff => {
+                decrease_color_by_height((130.0, 130.0, 110.0), self.relative_altitude)
+            }
+            CellType::Lake => {
+                increase_color_by_height((40.0, 100.0, 160.0), self.relative_altitude)
+            }
+        }
+    }
+}
+
+pub fn get_neighbors(map: &[Vec<Cell>], x: usize, y: usize) -> Vec<(usize, usize)> {
+    let mut neighbors: Vec<(usize, usize)> = vec![];
+
+    let h = map.len() as i32;
+    let w = map[0].len() as i32 * 2;
+
+    if x as i32 + 2 < w {
+        neighbors.push((x + 2, y));
+    }
+    if x as i32 + 1 < w && y as i32 - 1 >= 0 {
+        neighbors.push((x + 1, y - 1));
+    }
+    if x as i32 - 1 >= 0 && y as i32 - 1 >= 0 {
+        neighbors.push((x - 1, y - 1));
+    }
+    if x as i32 - 2 >= 0 {
+        neighbors.push((x - 2, y));
+    }
+    if x as i32 - 1 >= 0 && y as i32 + 1 < h {
+        neighbors.push((x - 1, y + 1));
+    }
+    if x as i32 + 1 < w && y as i32 + 1 < h {
+        neighbors.push((x + 1, y + 1));
+    }
+
+    neighbors
+}
+
+pub struct World {
+    pub map: Vec<Vec<Cell>>,
+    pub rivers: Vec<Vec<(usize, usize)>>,
+    pub layers: Layers,
+    // What the world was generated with; its thresholds classify the cells again.
+    pub config: WorldGenConfig,
+}
+
+pub fn get_world_size(map: &[Vec<Cell>]) -> (i32, i32) {
+    (map[0].len() as i32, map.len() as i32)
+}
+
+// The river id owning each cell, the first river to reach it, and how many rivers run
+// through it.
+pub fn river_layers(map: &[Vec<Cell>], rivers: &[Vec<(usize, usize)>]) -> Layers {
+    let (world_width, world_height) = get_world_size(map);
+    let mut layers = Layers::new(world_width, world_height);
+
+    let len = (world_width * world_height) as usize;
+    let mut owners = vec![NO_OWNER; len];
+    let mut flow = vec![0.0; len];
+    for (id, river) in rivers.iter().enumerate() {
+        for &(x, y) in river {
+            let Some(i) = layers.hex_index((x as i32, y as i32)) else {
+                continue;
+            };
+            if owners[i] == NO_OWNER {
+                owners[i] = id as u32 + 1;
+            }
+            flow[i] += 1.0;
+        }
+    }
+
+    layers
+        .insert(RIVER_LAYER, LayerData::Owner(owners))
+        .unwrap();
+    layers
+        .insert(RIVER_FLOW_LAYER, LayerData::Scalar(flow))
+        .unwrap();
+    layers
+}
+
+pub fn generate_river(
+    map: &mut [Vec<Cell>],
+    seed: u64,
+    config: &WorldGenConfig,
+) -> Vec<Vec<(usize, usize)>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let mut rivers = vec![];
+
+    let (world_width, world_height) = get_world_size(map);
+
+    for y in 0..world_height as usize {
+        for x in 0..world_width as usize {
+            if (matches!(map[y][x].cell_type, CellType::Cliff)
+                || matches!(map[y][x].cell_type, CellType::MediumCliff)
+                || matches!(map[y][x].cell_type, CellType::Mountain)
+                || matches!(map[y][x].cell_type, CellType::MediumMountain)
+                || matches!(map[y][x].cell_type, CellType::HighMountain))
+                && rng.random_bool(config.river_chance)
+            {
+                let mut x = x * 2 + y % 2;
+                let mut y = y;
+
+                let mut visited = vec![];
+
+                'finish: loop {
+                    map[y][x / 2].cell_type = CellType::River;
+                    visited.push((x, y));
+                    let neighbors_pos = get_neighbors(map, x, y);
+                    if neighbors_pos.len() < 6 {
+                        break;
+                    }
+                    let mut neighbors_pos: Vec<(usize, usize)> = neighbors_pos
+                        .iter()
+                        .filter(|cell_pos| !(visited.contains(cell_pos)))
+                        .cloned()
+                        .collect();
+                    if neighbors_pos.is_empty() {
+                        break;
+                    }
+                    neighbors_pos.shuffle(&mut rng);
+                    let mut min_altitude_cell_pos = neighbors_pos[0];
+                    for cell_pos in neighbors_pos {
+                        if map[cell_pos.1][cell_pos.0 / 2].altitude
+                            < map[min_altitude_cell_pos.1][min_altitude_cell_pos.0 / 2].altitude
+                        {
+                            min_altitude_cell_pos = cell_pos;
+                            break;
+                        }
+
+                        if map[cell_pos.1][cell_pos.0 / 2].cell_type.ends_river() {
+                            break 'finish;
+                        }
+                    }
+
+                    if map[min_altitude_cell_pos.1][min_altitude_cell_pos.0 / 2].altitude
+                        > map[y][x / 2].altitude + config.river_climb
+                    {
+                        break;
+                    }
+
+                    x = min_altitude_cell_pos.0;
+                    y = min_altitude_cell_pos.1;
+                }
+
+                rivers.push(visited);
+            }
+        }
+    }
+
+    rivers
+}
+
+pub fn generate_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+fn fbm(seed: u32, layer: &NoiseLayer) -> Fbm<Perlin> {
+    Fbm::<Perlin>::new(seed)
+        .set_octaves(layer.octaves)
+        .set_frequency(layer.frequency)
+        .set_lacunarity(layer.lacunarity)
+        .set_persistence(layer.persistence)
+}
+
+// Cell type and altitude above the lower bound of that type.
+pub fn classify(
+    altitude: f64,
+    temp: f64,
+    vegetation: f64,
+    beach_bias: f64,
+    cliff_bias: f64,
+    t: &BiomeThresholds,
+) -> (CellType, f64) {
+    if altitude > t.snow {
+        (CellType::Snow, altitude - t.snow)
+    } else if altitude > t.high_mountain {
+        (CellType::HighMountain, altitude - t.high_mountain)
+    } else if altitude > t.medium_mountain {
+        (CellType::MediumMountain, altitude - t.medium_mountain)
+    } else if altitude > t.mountain {
+        (CellType::Mountain, altitude - t.mountain)
+    } else if altitude > t.cliff_altitude && cliff_bias > t.medium_cliff_bias {
+        (CellType::MediumCliff, altitude - t.cliff_altitude)
+    } else if altitude > t.cliff_altitude && cliff_bias > t.cliff_bias {
+        (CellType::Cliff, altitude - t.cliff_altitude)
+    }
+    // else if altitude > 0.61 && altitude < 0.65 && lake_bias > 0.75 {
+    //     (CellType::Lake, altitude - 0.61)
+    // }
+    else if altitude > t.land {
+        if temp > t.sand_temperature {
+            (CellType::Sand, altitude - t.land)
+        } else if temp > t.dirt_temperature {
+            (CellType::Dirt, altitude - t.land)
+        } else if temp > t.grass_temperature {
+            if vegetation > t.tree_vegetation {
+                (CellType::Tree, altitude - t.land)
+            } else {
+                (CellType::Grass, altitude - t.land)
+            }
+        } else if temp > t.tundra_temperature {
+            (CellType::Tundra, altitude - t.land)
+        } else {
+            (CellType::Snow, altitude - t.land)
+        }
+    } else if altitude > t.beach_altitude && beach_bias > t.beach_bias && temp > t.beach_temperature
+    {
+        (CellType::Sand, altitude - t.shallow_water)
+    } else if altitude > t.shallow_water && temp > t.ice_temperature {
+        (CellType::ShallowWater, altitude - t.shallow_water)
+    } else if altitude > t.shallow_water {
+        (CellType::Ice, altitude - t.shallow_water)
+    } else if altitude > t.water {
+        (CellType::Water, altitude - t.water)
+    } else if altitude > t.medium_water {
+        (CellType::MediumWater, altitude - t.medium_water)
+    } else {
+        (CellType::DeepWater, altitude)
+    }
+}
+
+// Classifies every cell again from its stored fields under `climate`, marking the cells whose
+// type or color changed. River cells stay rivers unless a rising sea floods them, and swollen
+// rivers spill onto low land next to them.
+pub fn reclassify(
+    world: &mut World,
+    thresholds: &BiomeThresholds,
+    climate: &Climate,
+    dirty: &mut DirtyRegions,
+) -> usize {
+    let base_land = thresholds.land;
+    let thresholds = thresholds.with_sea_level(climate.sea_level);
+    let (world_width, world_height) = get_world_size(&world.map);
+
+    let mut on_river = vec![false; (world_width * world_height) as usize];
+    for &(x, y) in world.rivers.iter().flatten() {
+        on_river[x / 2 + y * world_width as usize] = true;
+    }
+
+    // Highest altitude a swollen river reaches on each cell next to it.
+    let mut flood_level = vec![f64::MIN; (world_width * world_height) as usize];
+    if climate.river_swell > 0.0 {
+        for &(x, y) in world.rivers.iter().flatten() {
+            let level = world.map[y][x / 2].altitude + climate.river_swell * RIVER_SWELL_HEIGHT;
+            for (nx, ny) in get_neighbors(&world.map, x, y) {
+                let i = nx / 2 + ny * world_width as usize;
+                flood_level[i] = flood_level[i].max(level);
+            }
+        }
+    }
+
+    let mut changed = 0;
+    for (y, row) in world.map.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            let (mut cell_type, relative_altitude) = classify(
+                cell.altitude,
+                cell.temperature + climate.temperature_offset_at(y, world_height),
+                cell.vegetation,
+                cell.beach_bias,
+                cell.cliff_bias,
+                &thresholds,
+            );
+            // Land the risen sea now covers; rivers already ran through the generated water.
+            let flooded = cell_type.is_water() && cell.altitude > base_land;
+            let i = x + y * world_width as usize;
+            let spilled = !cell_type.is_water() && cell.altitude <= flood_level[i];
+            if (on_river[i] && !flooded) || spilled {
+                cell_type = CellType::River;
+            }
+
+            if cell_type != cell.cell_type || relative_altitude != cell.relative_altitude {
+                cell.cell_type = cell_type;
+                cell.relative_altitude = relative_altitude;
+                dirty.mark(x, y);
+                changed += 1;
+            }
+        }
+    }
+
+    changed
+}
+
+// The noise functions of every generated field, shared by all generation threads.
+pub struct WorldNoise {
+    altitude: Fbm<Perlin>,
+    temperature: Fbm<Perlin>,
+    vegetation: Turbulence<Fbm<Perlin>, Perlin>,
+    beach: Fbm<Perlin>,
+    cliff: RidgedMulti<Perlin>,
+    lake: Fbm<Perlin>,
+    scale: f64,
+    cliff_exponent: f64,
+}
+
+impl WorldNoise {
+    pub fn new(seed: u64, config: &WorldGenConfig) -> WorldNoise {
+        let vegetation = fbm((seed as u32).wrapping_add(20), &config.vegetation);
+
+        WorldNoise {
+            altitude: fbm(seed as u32, &config.altitude),
+            temperature: fbm((seed as u32).wrapping_add(10), &config.temperature),
+            vegetation: Turbulence::<Fbm<Perlin>, Perlin>::new(vegetation)
+                .set_roughness(config.vegetation_roughness)
+                .set_power(config.vegetation_power),
+            beach: fbm((seed as u32).wrapping_add(30), &config.beach),
+            cliff: RidgedMulti::<Perlin>::new((seed as u32).wrapping_add(40))
+                .set_frequency(config.cliff.frequency)
+                .set_lacunarity(config.cliff.lacunarity)
+                .set_attenuation(config.cliff_attenuation)
+                .set_persistence(config.cliff.persistence)
+                .set_octaves(config.cliff.octaves),
+            lake: fbm((seed as u32).wrapping_add(50), &config.lake),
+            scale: config.scale,
+            cliff_exponent: config.cliff_exponent,
+        }
+    }
+
+    // Positions may be negative, the noise is defined everywhere.
+    fn point(self: &Self, x: i64, y: i64) -> [f64; 2] {
+        [x as f64 * self.scale, y as f64 * self.scale]
+    }
+
+    pub fn altitude(self: &Self, x: i64, y: i64) -> f64 {
+        (self.altitude.get(self.point(x, y)) + 1.0) / 2.0
+    }
+
+    pub fn temperature(self: &Self, x: i64, y: i64) -> f64 {
+        (self.temperature.get(self.point(x, y)) + 1.0) / 2.0
+    }
+
+    pub fn vegetation(self: &Self, x: i64, y: i64) -> f64 {
+        (self.vegetation.get(self.point(x, y)) + 1.0) / 2.0
+    }
+
+    pub fn beach_bias(self: &Self, x: i64, y: i64) -> f64 {
+        (self.beach.get(self.point(x, y)) + 1.0) / 2.0
+    }
+
+    pub fn cliff_bias(self: &Self, x: i64, y: i64) -> f64 {
+        ((self.cliff.get(self.point(x, y)) + 1.0) / 2.0).powf(self.cliff_exponent)
+    }
+
+    pub fn lake_bias(self: &Self, x: i64, y: i64) -> f64 {
+        (self.lake.get(self.point(x, y)) + 1.0) / 2.0
+    }
+
+    // Depends only on the position, so cells can be generated in any order.
+    pub fn cell(self: &Self, x: i64, y: i64, thresholds: &BiomeThresholds) -> Cell {
+        let altitude = self.altitude(x, y);
+        let temperature = self.temperature(x, y);
+        let vegetation = self.vegetation(x, y);
+        let beach_bias = self.beach_bias(x, y);
+        let cliff_bias = self.cliff_bias(x, y);
+        let lake_bias = self.lake_bias(x, y);
+
+        let (cell_type, relative_altitude) = classify(
+            altitude,
+            temperature,
+            vegetation,
+            beach_bias,
+            cliff_bias,
+            thresholds,
+        );
+
+        Cell {
+            cell_type,
+            altitude,
+            relative_altitude,
+            temperature,
+            vegetation,
+            beach_bias,
+            cliff_bias,
+            lake_bias,
+        }
+    }
+
+    pub fn row(self: &Self, y: usize, world_width: i32, thresholds: &BiomeThresholds) -> Vec<Cell> {
+        (0..world_width as usize)
+            .map(|x| self.cell(x as i64, y as i64, thresholds))
+            .collect()
+    }
+}
+
+// Traces the rivers of freshly generated cells and builds the world's layers.
+pub fn finish_world(mut world: Vec<Vec<Cell>>, seed: u64, config: &WorldGenConfig) -> World {
+    let rivers = generate_river(&mut world, seed, config);
+    let layers = river_layers(&world, &rivers);
+
+    World {
+        map: world,
+        rivers,
+        layers,
+        config: *config,
+    }
+}
+
+pub fn generate_world(seed: u64, world_width: i32, world_height: i32) -> World {
+    generate_world_with_config(seed, world_width, world_height, &WorldGenConfig::new())
+}
+
+// Generates rows on all cores; rivers are traced afterwards on one thread so the result
+// matches `generate_world_sequential` exactly.
+pub fn generate_world_with_config(
+    seed: u64,
+    world_width: i32,
+    world_height: i32,
+    config: &WorldGenConfig,
+) -> World {
+    let noise = WorldNoise::new(seed, config);
+    let world: Vec<Vec<Cell>> = (0..world_height as usize)
+        .into_par_iter()
+        .map(|y| noise.row(y, world_width, &config.thresholds))
+        .collect();
+
+    finish_world(world, seed, config)
+}
+
+pub fn generate_world_sequential(
+    seed: u64,
+    world_width: i32,
+    world_height: i32,
+    config: &WorldGenConfig,
+) -> World {
+    let noise = WorldNoise::new(seed, config);
+    let world: Vec<Vec<Cell>> = (0..world_height as usize)
+        .map(|y| noise.row(y, world_width, &config.thresholds))
+        .collect();
+
+    finish_world(world, seed, config)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Overlay {
+    Biome,
+    Altitude,
+    Temperature,
+    Vegetation,
+    BeachBias,
+    CliffBias,
+    LakeBias,
+    // One of the world's named layers.
+    Layer,
+}
+
+impl Overlay {
+    pub const ALL: [Overlay; 8] = [
+        Overlay::Biome,
+        Overlay::Altitude,
+        Overlay::Temperature,
+        Overlay::Vegetation,
+        Overlay::BeachBias,
+        Overlay::CliffBias,
+        Overlay::LakeBias,
+        Overlay::Layer,
+    ];
+
+    pub fn next(self: &Self) -> Overlay {
+        Overlay::ALL[(*self as usize + 1) % Overlay::ALL.len()]
+    }
+
+    pub fn previous(self: &Self) -> Overlay {
+        Overlay::ALL[(*self as usize + Overlay::ALL.len() - 1) % Overlay::ALL.len()]
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdgeMode {
+    None,
+    Coastlines,
+    Biomes,
+}
+
+impl EdgeMode {
+    pub fn next(self: &Self) -> EdgeMode {
+        match self {
+            EdgeMode::None => EdgeMode::Coastlines,
+            EdgeMode::Coastlines => EdgeMode::Biomes,
+            EdgeMode::Biomes => EdgeMode::None,
+        }
+    }
+}
+
+pub const FIELD_COUNT: usize = 8;
+
+fn cell_fields(cell: &Cell) -> [f32; FIELD_COUNT] {
+    [
+        cell.altitude as f32,
+        cell.temperature as f32,
+        cell.vegetation as f32,
+        cell.beach_bias as f32,
+        cell.cliff_bias as f32,
+        cell.lake_bias as f32,
+        cell.cell_type as u8 as f32,
+        if cell.cell_type.is_water() { 1.0 } else { 0.0 },
+    ]
+}
+
+fn cell_color(cell: &Cell) -> [f32; 4] {
+    let (r, g, b) = cell.get_color();
+    [r as f32, g as f32, b as f32, 255.0]
+}
+
+pub fn generate_world_fields(map: &[Vec<Cell>]) -> Vec<f32> {
+    let mut fields: Vec<f32> = vec![];
+
+    for row in map {
+        for cell in row {
+            fields.extend_from_slice(&cell_fields(cell));
+        }
+    }
+
+    fields
+}
+
+pub fn generate_world_colors(map: &[Vec<Cell>]) -> Vec<f32> {
+    let mut colors: Vec<f32> = vec![];
+
+    for row in map {
+        for cell in row {
+            colors.extend_from_slice(&cell_color(cell));
+        }
+    }
+
+    colors
+}
+
+pub fn update_world_fields(map: &[Vec<Cell>], fields: &mut [f32], dirty: &DirtyRegions) {
+    let world_width = get_world_size(map).0 as usize;
+
+    for (x, y) in dirty.cells() {
+        let i = (x + y * world_width) * FIELD_COUNT;
+        fields[i..i + FIELD_COUNT].copy_from_slice(&cell_fields(&map[y][x]));
+    }
+}
+
+pub fn update_world_colors(map: &[Vec<Cell>], colors: &mut [f32], dirty: &DirtyRegions) {
+    let world_width = get_world_size(map).0 as usize;
+
+    for (x, y) in dirty.cells() {
+        let i = (x + y * world_width) * 4;
+        colors[i..i + 4].copy_from_slice(&cell_color(&map[y][x]));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_generation_matches_sequential() {
+        let config = WorldGenConfig::new();
+        let parallel = generate_world_with_config(7, 48, 32, &config);
+        let sequential = generate_world_sequential(7, 48, 32, &config);
+
+        let fields: [fn(&Cell) -> f64; 7] = [
+            |cell| cell.altitude,
+            |cell| cell.relative_altitude,
+            |cell| cell.temperature,
+            |cell| cell.vegetation,
+            |cell| cell.beach_bias,
+            |cell| cell.cliff_bias,
+            |cell| cell.lake_bias,
+        ];
+        let cells = parallel
+            .map
+            .iter()
+            .flatten()
+            .zip(sequential.map.iter().flatten());
+        for (parallel, sequential) in cells {
+            assert_eq!(parallel.cell_type, sequential.cell_type);
+            for field in fields {
+                assert_eq!(field(parallel).to_bits(), field(sequential).to_bits());
+            }
+        }
+        assert_eq!(parallel.rivers, sequential.rivers);
+    }
+
+    #[test]
+    fn rising_sea_floods_only_the_rivers_it_covers() {
+        let mut config = WorldGenConfig::new();
+        config.river_chance = 0.05;
+        let mut world = generate_world_with_config(3, 300, 200, &config);
+        let mut dirty = DirtyRegions::new(300, 200);
+        let rivers: Vec<(usize, usize)> = world
+            .rivers
+            .iter()
+            .flatten()
+            .map(|&(x, y)| (x / 2, y))
+            .collect();
+        assert!(!rivers.is_empty());
+
+        assert_eq!(
+            reclassify(&mut world, &config.thresholds, &Climate::new(), &mut dirty),
+            0
+        );
+
+        let mut climate = Climate::new();
+        climate.sea_level = 0.05;
+        reclassify(&mut world, &config.thresholds, &climate, &mut dirty);
+        let risen = config.thresholds.with_sea_level(climate.sea_level);
+        let mut drowned = 0;
+        for &(x, y) in &rivers {
+            let cell = &world.map[y][x];
+            let (under_sea, _) = classify(
+                cell.altitude,
+                cell.temperature,
+                cell.vegetation,
+                cell.beach_bias,
+                cell.cliff_bias,
+                &risen,
+            );
+            let flooded = under_sea.is_water() && cell.altitude > config.thresholds.land;
+            assert_eq!(
+                cell.cell_type != CellType::River,
+                flooded,
+                "cell {} {}",
+                x,
+                y
+            );
+            drowned += flooded as usize;
+        }
+        assert!(drowned > 0 && drowned < rivers.len());
+
+        climate.sea_level = -0.05;
+        reclassify(&mut world, &config.thresholds, &climate, &mut dirty);
+        for &(x, y) in &rivers {
+            assert_eq!(world.map[y][x].cell_type, CellType::River);
+        }
+    }
+}
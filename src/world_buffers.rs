@@ -0,0 +1,194 @@
+use civilizations::{
+    Cell, DirtyRegions, Layer, generate_world_colors, generate_world_fields, get_world_size,
+    lod::{self, ColorLevel},
+    update_world_colors, update_world_fields,
+    world::FIELD_COUNT,
+};
+use gl::types::{GLintptr, GLsizeiptr, GLuint, GLvoid};
+
+fn create_ssbo<T>(data: &[T], binding: GLuint) -> GLuint {
+    unsafe {
+        let mut ssbo: GLuint = 0;
+        gl::GenBuffers(1, &mut ssbo);
+        gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
+
+        gl::BufferData(
+            gl::SHADER_STORAGE_BUFFER,
+            std::mem::size_of_val(data) as GLsizeiptr,
+            data.as_ptr() as *const GLvoid,
+            gl::DYNAMIC_DRAW,
+        );
+
+        gl::BindBufferBase(gl::SHADER_STORAGE_BUFFER, binding, ssbo);
+
+        ssbo
+    }
+}
+
+fn update_ssbo<T>(ssbo: GLuint, offset: usize, data: &[T]) {
+    unsafe {
+        gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
+        gl::BufferSubData(
+            gl::SHADER_STORAGE_BUFFER,
+            (offset * std::mem::size_of::<T>()) as GLintptr,
+            std::mem::size_of_val(data) as GLsizeiptr,
+            data.as_ptr() as *const GLvoid,
+        );
+    }
+}
+
+fn read_ssbo<T>(ssbo: GLuint, data: &mut [T]) {
+    unsafe {
+        gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, ssbo);
+        gl::GetBufferSubData(
+            gl::SHADER_STORAGE_BUFFER,
+            0,
+            std::mem::size_of_val(data) as GLsizeiptr,
+            data.as_mut_ptr() as *mut GLvoid,
+        );
+    }
+}
+
+pub struct WorldBuffers {
+    pub world_size: (i32, i32),
+    pub lod_levels: i32,
+    pub lod_offsets: [i32; lod::MAX_LOD_LEVELS],
+    pub lod_widths: [i32; lod::MAX_LOD_LEVELS],
+    // Kind of the layer in `layer_ssbo` as the shader's `LAYER_*` constants, and its range.
+    pub layer_kind: i32,
+    pub layer_range: (f32, f32),
+    colors: Vec<f32>,
+    fields: Vec<f32>,
+    levels: Vec<ColorLevel>,
+    colors_ssbo: GLuint,
+    fields_ssbo: GLuint,
+    lod_ssbo: GLuint,
+    layer_ssbo: GLuint,
+}
+
+// `LAYER_NONE` of the shader.
+const NO_LAYER: i32 = -1;
+
+impl WorldBuffers {
+    pub fn new(map: &[Vec<Cell>]) -> WorldBuffers {
+        let world_size = get_world_size(map);
+        let colors = generate_world_colors(map);
+        let fields = generate_world_fields(map);
+        let levels = lod::generate_color_pyramid(&colors, world_size.0, world_size.1);
+
+        let mut packed: Vec<u32> = vec![];
+        let mut lod_offsets = [0; lod::MAX_LOD_LEVELS];
+        let mut lod_widths = [0; lod::MAX_LOD_LEVELS];
+        for (i, level) in levels.iter().enumerate() {
+            lod_offsets[i + 1] = packed.len() as i32;
+            lod_widths[i + 1] = level.width;
+            packed.extend(level.colors.chunks(4).map(lod::pack_color));
+        }
+
+        WorldBuffers {
+            world_size,
+            lod_levels: levels.len() as i32,
+            lod_offsets,
+            lod_widths,
+            layer_kind: NO_LAYER,
+            layer_range: (0.0, 0.0),
+            colors_ssbo: create_ssbo(&colors, 0),
+            fields_ssbo: create_ssbo(&fields, 1),
+            lod_ssbo: create_ssbo(&packed, 2),
+            layer_ssbo: create_ssbo::<f32>(&vec![], 5),
+            colors,
+            fields,
+            levels,
+        }
+    }
+
+    pub fn update(self: &mut Self, map: &[Vec<Cell>], dirty: &DirtyRegions) {
+        if dirty.is_empty() {
+            return;
+        }
+
+        update_world_colors(map, &mut self.colors, dirty);
+        update_world_fields(map, &mut self.fields, dirty);
+        lod::update_color_pyramid(
+            &mut self.levels,
+            &self.colors,
+            self.world_size.0,
+            self.world_size.1,
+            dirty,
+        );
+
+        for (start, end) in dirty.ranges() {
+            update_ssbo(
+                self.colors_ssbo,
+                start * 4,
+                &self.colors[start * 4..end * 4],
+            );
+            update_ssbo(
+                self.fields_ssbo,
+                start * FIELD_COUNT,
+                &self.fields[start * FIELD_COUNT..end * FIELD_COUNT],
+            );
+        }
+
+        let mut level_dirty = dirty.downsample();
+        for (i, level) in self.levels.iter().enumerate() {
+            for (start, end) in level_dirty.ranges() {
+                let packed: Vec<u32> = level.colors[start * 4..end * 4]
+                    .chunks(4)
+                    .map(lod::pack_color)
+                    .collect();
+                update_ssbo(
+                    self.lod_ssbo,
+                    self.lod_offsets[i + 1] as usize + start,
+                    &packed,
+                );
+            }
+            level_dirty = level_dirty.downsample();
+        }
+    }
+
+    // Replaces the layer shown by the layer overlay.
+    pub fn upload_layer(self: &mut Self, layer: Option<&Layer>) {
+        let values = layer.map_or(vec![], |layer| layer.data.values());
+        unsafe {
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.layer_ssbo);
+            gl::BufferData(
+                gl::SHADER_STORAGE_BUFFER,
+                (values.len() * std::mem::size_of::<f32>()) as GLsizeiptr,
+                values.as_ptr() as *const GLvoid,
+                gl::DYNAMIC_DRAW,
+            );
+        }
+
+        self.layer_kind = layer.map_or(NO_LAYER, |layer| layer.data.kind() as i32);
+        self.layer_range = layer.map_or((0.0, 0.0), |layer| layer.data.range());
+    }
+
+    pub fn colors(self: &Self) -> &[f32] {
+        &self.colors
+    }
+
+    // Picks up colors and fields written on the GPU and rebuilds the LOD levels from them.
+    pub fn read_back(self: &mut Self) {
+        read_ssbo(self.colors_ssbo, &mut self.colors);
+        read_ssbo(self.fields_ssbo, &mut self.fields);
+
+        self.levels =
+            lod::generate_color_pyramid(&self.colors, self.world_size.0, self.world_size.1);
+        for (i, level) in self.levels.iter().enumerate() {
+            let packed: Vec<u32> = level.colors.chunks(4).map(lod::pack_color).collect();
+            update_ssbo(self.lod_ssbo, self.lod_offsets[i + 1] as usize, &packed);
+        }
+    }
+}
+
+impl Drop for WorldBuffers {
+    fn drop(self: &mut Self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.colors_ssbo);
+            gl::DeleteBuffers(1, &self.fields_ssbo);
+            gl::DeleteBuffers(1, &self.lod_ssbo);
+            gl::DeleteBuffers(1, &self.layer_ssbo);
+        }
+    }
+}